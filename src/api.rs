@@ -1,24 +1,301 @@
+use crate::cache::{CacheKey, CachePolicy, CacheStats, DiskCacheMode, ResponseCache};
 use crate::error::{DatenoError, Result};
+use crate::filters::{self, Filter};
+use crate::timestamps;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::runtime::Runtime;
 use url::Url;
 
+/// Runtime, HTTP client and client pool shared by every query in the
+/// process; created on first use and released by [`shutdown_shared`]
+static SHARED: Mutex<Option<SharedResources>> = Mutex::new(None);
+
+struct SharedResources {
+    runtime: Arc<Runtime>,
+    http: reqwest::Client,
+    clients: HashMap<ClientKey, Arc<DatenoClient>>,
+}
+
+/// Configuration that distinguishes pooled clients
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ClientKey {
+    base_url: String,
+    api_key: Option<String>,
+    retry: RetryPolicy,
+    cache: CachePolicy,
+}
+
+/// Response cache shared by every client in the process. Kept apart from
+/// [`SHARED`] so cached responses survive [`shutdown_shared`].
+static RESPONSE_CACHE: Mutex<Option<ResponseCache>> = Mutex::new(None);
+
+/// Run `f` against the response cache, creating it on first use
+fn with_cache<T>(f: impl FnOnce(&mut ResponseCache) -> T) -> T {
+    let mut cache = match RESPONSE_CACHE.lock() {
+        Ok(cache) => cache,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    f(cache.get_or_insert_with(ResponseCache::new))
+}
+
+/// Counters of the process-wide response cache
+pub fn cache_stats() -> CacheStats {
+    with_cache(|cache| cache.stats())
+}
+
+/// Drop every cached response, returning how many were removed
+pub fn clear_cache() -> usize {
+    with_cache(|cache| cache.clear())
+}
+
+impl SharedResources {
+    fn new() -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .thread_name("dateno-io")
+            .enable_all()
+            .build()
+            .map_err(|e| DatenoError::Runtime(format!("Failed to create Tokio runtime: {}", e)))?;
+
+        let http = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(10))
+            .timeout(Duration::from_secs(60))
+            .pool_max_idle_per_host(10)
+            .user_agent("dateno-duckdb-ext/0.1.0")
+            .build()
+            .map_err(|e| DatenoError::Http(e))?;
+
+        Ok(Self {
+            runtime: Arc::new(runtime),
+            http,
+            clients: HashMap::new(),
+        })
+    }
+}
+
+/// Run `f` against the shared resources, creating them on first use
+fn with_shared<T>(f: impl FnOnce(&mut SharedResources) -> Result<T>) -> Result<T> {
+    let mut shared = SHARED
+        .lock()
+        .map_err(|_| DatenoError::Runtime("Shared client state lock poisoned".to_string()))?;
+    if shared.is_none() {
+        *shared = Some(SharedResources::new()?);
+    }
+    f(shared.as_mut().expect("shared resources initialised above"))
+}
+
+/// Drop the pooled clients and shut down the shared runtime.
+///
+/// Queries still holding a client keep the runtime alive until they finish;
+/// the next client request after shutdown starts a fresh runtime.
+pub fn shutdown_shared() {
+    let resources = match SHARED.lock() {
+        Ok(mut shared) => shared.take(),
+        Err(poisoned) => poisoned.into_inner().take(),
+    };
+    if let Some(resources) = resources {
+        drop(resources.clients);
+        if let Ok(runtime) = Arc::try_unwrap(resources.runtime) {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+/// Databases the extension's functions are registered with
+static LIVE_DATABASES: AtomicUsize = AtomicUsize::new(0);
+
+/// Keeps the shared resources in use while a database has the extension loaded.
+///
+/// DuckDB has no extension unload hook, so a guard is attached to each
+/// registered table function and dropped with the database's catalog; once
+/// the last database is closed the shared resources are released.
+pub struct SharedGuard(());
+
+impl SharedGuard {
+    /// Count one more database using the extension
+    pub fn acquire() -> Arc<Self> {
+        LIVE_DATABASES.fetch_add(1, Ordering::SeqCst);
+        Arc::new(Self(()))
+    }
+}
+
+impl Drop for SharedGuard {
+    fn drop(&mut self) {
+        if LIVE_DATABASES.fetch_sub(1, Ordering::SeqCst) == 1 {
+            shutdown_shared();
+        }
+    }
+}
+
 /// Dateno API client
 pub struct DatenoClient {
     client: reqwest::Client,
     base_url: Url,
     api_key: Option<String>,
     runtime: Arc<Runtime>,
+    retry: RetryPolicy,
+    cache: CachePolicy,
+}
+
+/// Retry policy for transient API failures (429, 502-504, timeouts and
+/// connection errors)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first request
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled for every further attempt
+    pub base_delay: Duration,
+    /// Upper bound for any single delay, including `Retry-After` hints
+    pub max_delay: Duration,
+    /// Randomise each delay to between 50% and 100% of its nominal value
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy that never retries
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Delay before retry number `retry` (1-based), honouring a server hint if present
+    fn delay(&self, retry: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(hint) = retry_after {
+            return hint.min(self.max_delay);
+        }
+
+        let exponent = retry.saturating_sub(1).min(16);
+        let nominal = self.base_delay.saturating_mul(1 << exponent).min(self.max_delay);
+        if !self.jitter {
+            return nominal;
+        }
+
+        // Cheap jitter source; no need for a full RNG dependency here
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.subsec_nanos())
+            .unwrap_or(0);
+        let factor = 0.5 + (nanos % 1000) as f64 / 2000.0;
+        nominal.mul_f64(factor)
+    }
+}
+
+/// Public Dateno API endpoint used when no base URL is configured
+pub const DEFAULT_BASE_URL: &str = "https://api.dateno.io";
+
+/// Search endpoint, relative to the base URL
+const SEARCH_PATH: &str = "search/0.2/query";
+
+/// Record-by-id endpoint, relative to the base URL; the id is appended
+const ENTRY_PATH: &str = "search/0.2/entry/";
+
+/// Maximum number of results the API returns for a single request
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+/// Pages a search with local filters fetches at most, unless configured otherwise
+pub const DEFAULT_MAX_SCAN_PAGES: u32 = 50;
+
+/// Fields search results can be ordered by; `relevance` is the API's default order
+pub const SORT_FIELDS: [&str; 4] = ["relevance", "updated_at", "created_at", "title"];
+
+/// A validated sort order for [`SearchRequest::sort`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortOrder {
+    pub field: &'static str,
+    pub descending: bool,
+}
+
+impl SortOrder {
+    /// Parse `field`, `field asc`, `field desc` or `-field` (descending).
+    /// Fields sort ascending by default, except relevance which is always descending.
+    pub fn parse(s: &str) -> Result<Self> {
+        let invalid = || DatenoError::InvalidSort(s.to_string());
+        let spec = s.trim().to_ascii_lowercase();
+
+        let (name, descending) = match spec.strip_prefix('-') {
+            Some(name) => (name.trim().to_string(), Some(true)),
+            None => {
+                let mut words = spec.split_whitespace();
+                let name = words.next().ok_or_else(invalid)?.to_string();
+                let descending = match words.next() {
+                    None => None,
+                    Some("asc") => Some(false),
+                    Some("desc") => Some(true),
+                    Some(_) => return Err(invalid()),
+                };
+                if words.next().is_some() {
+                    return Err(invalid());
+                }
+                (name, descending)
+            }
+        };
+
+        let field = SORT_FIELDS
+            .iter()
+            .find(|field| **field == name)
+            .ok_or_else(invalid)?;
+        if *field == "relevance" {
+            return match descending {
+                Some(false) => Err(invalid()),
+                _ => Ok(Self { field, descending: true }),
+            };
+        }
+        Ok(Self {
+            field,
+            descending: descending.unwrap_or(false),
+        })
+    }
+
+    /// Value for the API's `sort` parameter; `None` keeps the default relevance order
+    pub fn to_param(self) -> Option<String> {
+        if self.field == "relevance" {
+            return None;
+        }
+        let direction = if self.descending { "desc" } else { "asc" };
+        Some(format!("{} {}", self.field, direction))
+    }
 }
 
 /// Search request parameters
+#[derive(Debug, Clone, Default)]
 pub struct SearchRequest {
     pub query: String,
     pub filters: Vec<String>,
     pub limit: i64,
+    pub offset: i64,
+    /// Sort order passed through to the API, e.g. `updated_at desc`;
+    /// see [`SortOrder`] for the accepted forms
+    pub sort: Option<String>,
+    /// Opaque token from [`SearchResponse::next_cursor`] to continue a previous
+    /// result set; used instead of `offset` when set
+    pub cursor: Option<String>,
+    /// Fields to return for each result; empty requests the full record
+    pub fields: Vec<String>,
+    /// Fields to aggregate hit counts over
+    pub facets: Vec<String>,
+    /// Keep each result's full original record in [`Dataset::raw`]
+    pub keep_raw: bool,
+    /// Drop results that cannot be decoded instead of failing the request;
+    /// the number dropped is reported in [`SearchResponse::skipped`]
+    pub skip_malformed: bool,
 }
 
 /// Dataset metadata from Dateno API
@@ -30,8 +307,8 @@ pub struct Dataset {
     pub description: String,
     #[serde(default)]
     pub format: String,
-    #[serde(default)]
-    pub source: String,
+    #[serde(default, deserialize_with = "deserialize_source")]
+    pub source: Source,
     #[serde(default)]
     pub url: String,
     #[serde(default, deserialize_with = "deserialize_optional_datetime")]
@@ -40,6 +317,63 @@ pub struct Dataset {
     pub updated_at: Option<DateTime<Utc>>,
     #[serde(default)]
     pub formats: Vec<String>,
+    /// Downloadable files attached to the dataset
+    #[serde(default)]
+    pub resources: Vec<Resource>,
+    /// Relevance score of a search hit
+    #[serde(default, alias = "_score")]
+    pub score: Option<f64>,
+    /// Problems found while decoding the record, such as unparseable timestamps
+    #[serde(skip)]
+    pub warnings: Vec<String>,
+    /// Full original record, kept when the caller asks for it
+    /// ([`SearchRequest::keep_raw`] or [`DatenoClient::get_entry`])
+    #[serde(skip)]
+    pub raw: Option<serde_json::Value>,
+}
+
+/// Catalog a dataset was harvested from, with its coverage metadata
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct Source {
+    #[serde(default, alias = "name")]
+    pub catalog_name: String,
+    #[serde(default, alias = "url")]
+    pub catalog_url: String,
+    #[serde(default, deserialize_with = "deserialize_entity_name")]
+    pub software: String,
+    #[serde(default)]
+    pub countries: Vec<SourceEntity>,
+    #[serde(default, alias = "langs")]
+    pub languages: Vec<SourceEntity>,
+    #[serde(default)]
+    pub organizations: Vec<SourceEntity>,
+}
+
+/// Country, language or organization attached to a source
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct SourceEntity {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+}
+
+/// A downloadable file of a dataset
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct Resource {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub format: String,
+    #[serde(default)]
+    pub mimetype: String,
+    /// Size in bytes, when the catalog reports it
+    #[serde(default, deserialize_with = "deserialize_optional_size")]
+    pub size: Option<u64>,
+    #[serde(default, deserialize_with = "deserialize_optional_datetime")]
+    pub last_modified: Option<DateTime<Utc>>,
 }
 
 /// Search response from Dateno API
@@ -47,128 +381,790 @@ pub struct Dataset {
 pub struct SearchResponse {
     pub total: usize,
     pub results: Vec<Dataset>,
+    /// Hit counts per facet field, present when facets were requested
+    #[serde(default)]
+    pub facets: HashMap<String, Vec<FacetBucket>>,
+    /// Token for fetching the page after this one, when the API provides it
+    pub next_cursor: Option<String>,
+    /// Results dropped because they could not be decoded
+    #[serde(skip)]
+    pub skipped: usize,
+}
+
+/// Search response with results left undecoded, so each record can be
+/// checked on its own
+#[derive(Deserialize)]
+struct RawSearchResponse {
+    total: usize,
+    results: Vec<serde_json::Value>,
+    #[serde(default)]
+    facets: HashMap<String, Vec<FacetBucket>>,
+    #[serde(default, alias = "cursor")]
+    next_cursor: Option<String>,
+}
+
+/// One value of a facet field and the number of matching datasets
+#[derive(Debug, Clone, Deserialize)]
+pub struct FacetBucket {
+    #[serde(alias = "key")]
+    pub value: serde_json::Value,
+    #[serde(alias = "doc_count")]
+    pub count: u64,
+}
+
+/// A flattened facet count, as returned by [`DatenoClient::facets`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FacetCount {
+    pub facet: String,
+    pub value: String,
+    pub count: u64,
 }
 
+/// Timestamps are parsed leniently: a value in an unrecognised format becomes
+/// `None` instead of failing the record, and is reported in [`Dataset::warnings`]
 fn deserialize_optional_datetime<'de, D>(deserializer: D) -> std::result::Result<Option<DateTime<Utc>>, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
-    let s: Option<String> = Option::deserialize(deserializer)?;
-    match s {
-        Some(s) => {
-            DateTime::parse_from_rfc3339(&s)
-                .map(|dt| Some(dt.with_timezone(&Utc)))
-                .map_err(serde::de::Error::custom)
-        }
-        None => Ok(None),
+    let value: Option<serde_json::Value> = Option::deserialize(deserializer)?;
+    Ok(value.as_ref().and_then(timestamps::parse_value))
+}
+
+/// Accept either a full source object or, as older responses send, just the catalog name
+fn deserialize_source<'de, D>(deserializer: D) -> std::result::Result<Source, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum SourceRepr {
+        Name(String),
+        Full(Source),
+    }
+
+    Ok(match Option::<SourceRepr>::deserialize(deserializer)? {
+        Some(SourceRepr::Name(catalog_name)) => Source {
+            catalog_name,
+            ..Default::default()
+        },
+        Some(SourceRepr::Full(source)) => source,
+        None => Source::default(),
+    })
+}
+
+/// Accept a plain name or an `{"id": ..., "name": ...}` object
+fn deserialize_entity_name<'de, D>(deserializer: D) -> std::result::Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NameRepr {
+        Name(String),
+        Entity(SourceEntity),
     }
+
+    Ok(match Option::<NameRepr>::deserialize(deserializer)? {
+        Some(NameRepr::Name(name)) => name,
+        Some(NameRepr::Entity(entity)) if !entity.name.is_empty() => entity.name,
+        Some(NameRepr::Entity(entity)) => entity.id,
+        None => String::new(),
+    })
+}
+
+/// Accept a byte count as a number or, as some catalogs send it, a numeric string
+fn deserialize_optional_size<'de, D>(deserializer: D) -> std::result::Result<Option<u64>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum SizeRepr {
+        Number(u64),
+        Text(String),
+    }
+
+    Ok(match Option::<SizeRepr>::deserialize(deserializer)? {
+        Some(SizeRepr::Number(n)) => Some(n),
+        Some(SizeRepr::Text(s)) => s.trim().parse().ok(),
+        None => None,
+    })
 }
 
 impl DatenoClient {
-    /// Create a new Dateno client
-    pub fn new(api_key: Option<String>) -> Result<Self> {
-        let runtime = Runtime::new()
-            .map_err(|e| DatenoError::Runtime(format!("Failed to create Tokio runtime: {}", e)))?;
+    /// Create a new Dateno client for the API rooted at `base_url`.
+    ///
+    /// The client runs on the process-wide runtime and shares its HTTP
+    /// connection pool with every other client. It does not cache responses
+    /// unless given a policy with [`DatenoClient::with_cache_policy`].
+    pub fn new(base_url: &str, api_key: Option<String>) -> Result<Self> {
+        with_shared(|shared| Self::from_shared(shared, base_url, api_key))
+    }
 
-        let client = reqwest::Client::builder()
-            .connect_timeout(Duration::from_secs(10))
-            .timeout(Duration::from_secs(60))
-            .pool_max_idle_per_host(10)
-            .user_agent("dateno-duckdb-ext/0.1.0")
-            .build()
-            .map_err(|e| DatenoError::Http(e))?;
+    /// Return the pooled client for this configuration, creating it on first use
+    pub fn shared(
+        base_url: &str,
+        api_key: Option<String>,
+        retry: RetryPolicy,
+        cache: CachePolicy,
+    ) -> Result<Arc<Self>> {
+        let key = ClientKey {
+            base_url: base_url.to_string(),
+            api_key,
+            retry,
+            cache,
+        };
+        with_shared(|shared| {
+            if let Some(client) = shared.clients.get(&key) {
+                return Ok(client.clone());
+            }
+            let client = Arc::new(
+                Self::from_shared(shared, &key.base_url, key.api_key.clone())?
+                    .with_retry_policy(key.retry.clone())
+                    .with_cache_policy(key.cache.clone()),
+            );
+            shared.clients.insert(key, client.clone());
+            Ok(client)
+        })
+    }
 
-        let base_url = Url::parse("https://api.dateno.io/search/0.2/query")
-            .map_err(|e| DatenoError::Url(e))?;
+    fn from_shared(shared: &SharedResources, base_url: &str, api_key: Option<String>) -> Result<Self> {
+        // Keep a trailing slash so endpoint paths join under any path prefix
+        let mut base_url = Url::parse(base_url).map_err(|e| DatenoError::Url(e))?;
+        if !base_url.path().ends_with('/') {
+            let path = format!("{}/", base_url.path());
+            base_url.set_path(&path);
+        }
 
         Ok(Self {
-            client,
+            client: shared.http.clone(),
             base_url,
             api_key,
-            runtime: Arc::new(runtime),
+            runtime: shared.runtime.clone(),
+            retry: RetryPolicy::default(),
+            cache: CachePolicy::none(),
         })
     }
 
+    /// Replace the retry policy used for every request made by this client
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Replace the response cache policy used for every request made by this client
+    pub fn with_cache_policy(mut self, cache: CachePolicy) -> Self {
+        self.cache = cache;
+        self
+    }
+
     /// Execute a search request synchronously
     pub fn search(&self, request: SearchRequest) -> Result<SearchResponse> {
         // Validate limit
-        if request.limit < 1 || request.limit > 100 {
+        if request.limit < 1 || request.limit > MAX_PAGE_SIZE {
             return Err(DatenoError::InvalidLimit(request.limit));
         }
 
-        let runtime = self.runtime.clone();
-        let client = self.client.clone();
-        let base_url = self.base_url.clone();
-        let api_key = self.api_key.clone();
+        self.execute_search(request)
+    }
 
-        runtime.block_on(async move {
-            Self::search_async(client, base_url, api_key, request).await
-        })
+    /// Total number of datasets matching `query` and `filters`, without fetching any
+    pub fn count(&self, query: &str, filters: &[String]) -> Result<usize> {
+        let request = SearchRequest {
+            query: query.to_string(),
+            filters: filters.to_vec(),
+            limit: 0,
+            ..Default::default()
+        };
+        Ok(self.execute_search(request)?.total)
     }
 
-    async fn search_async(
-        client: reqwest::Client,
-        mut base_url: Url,
-        api_key: Option<String>,
-        request: SearchRequest,
-    ) -> Result<SearchResponse> {
+    /// Run a search request without validating the limit, so zero-row
+    /// count and facet requests can share the search path
+    fn execute_search(&self, mut request: SearchRequest) -> Result<SearchResponse> {
+        // Snapshots on disk hold full records, so a query replays from them
+        // whichever columns it selects
+        if self.cache.active_disk().is_some() {
+            request.fields.clear();
+        }
+
+        let url = Self::search_url(self.base_url.join(SEARCH_PATH)?, &request)?;
+        let body = self.fetch(url)?;
+
+        // Parse JSON response
+        parse_search_response(&body, &request)
+    }
+
+    /// Count matching datasets per value of each field in `facet_fields`,
+    /// without fetching any results
+    pub fn facets(&self, query: &str, filters: &[String], facet_fields: &[String]) -> Result<Vec<FacetCount>> {
+        if facet_fields.is_empty() {
+            return Err(DatenoError::MissingParameter("facet_fields".to_string()));
+        }
+
+        let request = SearchRequest {
+            query: query.to_string(),
+            filters: filters.to_vec(),
+            limit: 0,
+            facets: facet_fields.to_vec(),
+            ..Default::default()
+        };
+        let mut response = self.execute_search(request)?;
+
+        // Keep the caller's facet order; buckets stay in API order
+        let mut counts = Vec::new();
+        for facet in facet_fields {
+            for bucket in response.facets.remove(facet).unwrap_or_default() {
+                let value = match bucket.value {
+                    serde_json::Value::String(s) => s,
+                    other => other.to_string(),
+                };
+                counts.push(FacetCount {
+                    facet: facet.clone(),
+                    value,
+                    count: bucket.count,
+                });
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Fetch the full record for a single dataset id
+    pub fn get_entry(&self, id: &str) -> Result<Dataset> {
+        if id.trim().is_empty() {
+            return Err(DatenoError::MissingParameter("id".to_string()));
+        }
+
+        let mut url = self.base_url.join(ENTRY_PATH)?;
+        url.path_segments_mut()
+            .map_err(|_| DatenoError::Url(url::ParseError::RelativeUrlWithCannotBeABaseBase))?
+            .pop_if_empty()
+            .push(id);
+
+        let body = self.fetch(url)?;
+        parse_entry(&body)
+    }
+
+    /// Build the search URL for `request` under the search endpoint `base_url`
+    fn search_url(mut base_url: Url, request: &SearchRequest) -> Result<Url> {
         // Build query parameters
         {
             let mut pairs = base_url.query_pairs_mut();
             pairs.append_pair("q", &request.query);
             pairs.append_pair("limit", &request.limit.to_string());
+            if let Some(cursor) = &request.cursor {
+                pairs.append_pair("cursor", cursor);
+            } else if request.offset > 0 {
+                pairs.append_pair("offset", &request.offset.to_string());
+            }
+            if let Some(sort) = &request.sort {
+                pairs.append_pair("sort", sort);
+            }
+            if !request.fields.is_empty() {
+                pairs.append_pair("fields", &request.fields.join(","));
+            }
+            for facet in &request.facets {
+                pairs.append_pair("facets", facet);
+            }
 
             // API key is sent as Bearer token in Authorization header (not query parameter)
 
             // Add filters
             for filter in &request.filters {
                 let (key, value) = parse_filter(filter)?;
-                let encoded = format!("\"{}\"=\"{}\"", key, value);
-                pairs.append_pair("filters", &encoded);
+                pairs.append_pair("filters", &encode_filter(&key, &value));
+            }
+        }
+        Ok(base_url)
+    }
+
+    /// GET `url` and return the response body, answering from the in-memory
+    /// and on-disk caches when the client's policy allows. Only successful
+    /// responses are cached.
+    fn fetch(&self, url: Url) -> Result<String> {
+        let key = CacheKey::new(url.as_str(), self.api_key.as_deref());
+        let disk = self.cache.active_disk();
+        let refresh = disk.is_some_and(|disk| disk.mode == DiskCacheMode::Refresh);
+
+        if self.cache.memory_enabled() && !refresh {
+            if let Some(body) = with_cache(|cache| cache.get(&key, self.cache.ttl)) {
+                return Ok(body);
+            }
+        }
+
+        if let Some(disk) = disk {
+            if disk.mode.reads() {
+                if let Some(body) = disk.load(&key)? {
+                    self.remember(key, &body);
+                    return Ok(body);
+                }
+            }
+            if disk.mode == DiskCacheMode::ReadOnly {
+                return Err(DatenoError::CacheMiss {
+                    url: key.url().to_string(),
+                    dir: disk.dir.display().to_string(),
+                });
+            }
+        }
+
+        let client = self.client.clone();
+        let api_key = self.api_key.clone();
+        let retry = self.retry.clone();
+        let body = self.runtime.block_on(async move {
+            Self::get_with_retry(&client, url, api_key.as_deref(), &retry).await
+        })?;
+
+        if let Some(disk) = disk.filter(|disk| disk.mode.writes()) {
+            disk.store(&key, &body)?;
+        }
+        self.remember(key, &body);
+        Ok(body)
+    }
+
+    /// Keep a response in the in-memory cache, if enabled
+    fn remember(&self, key: CacheKey, body: &str) {
+        if self.cache.memory_enabled() {
+            let max_entries = self.cache.max_entries;
+            with_cache(|cache| cache.insert(key, body.to_string(), max_entries));
+        }
+    }
+
+    /// Issue a GET request, retrying transient failures according to `retry`,
+    /// and return the body of the first successful response
+    async fn get_with_retry(
+        client: &reqwest::Client,
+        url: Url,
+        api_key: Option<&str>,
+        retry: &RetryPolicy,
+    ) -> Result<String> {
+        let max_attempts = retry.max_attempts.max(1);
+        let mut attempt = 1;
+
+        loop {
+            let failure = match Self::get_once(client, url.clone(), api_key).await {
+                Ok(body) => return Ok(body),
+                Err(failure) => failure,
+            };
+
+            if !failure.retryable || attempt >= max_attempts {
+                if attempt > 1 {
+                    return Err(DatenoError::RetriesExhausted {
+                        attempts: attempt,
+                        source: Box::new(failure.error),
+                    });
+                }
+                return Err(failure.error);
             }
+
+            tokio::time::sleep(retry.delay(attempt, failure.retry_after)).await;
+            attempt += 1;
         }
+    }
 
+    /// Issue a single GET request and return the response body
+    async fn get_once(
+        client: &reqwest::Client,
+        url: Url,
+        api_key: Option<&str>,
+    ) -> std::result::Result<String, RequestFailure> {
         // Build request
-        let mut http_request = client.get(base_url);
+        let mut http_request = client.get(url);
 
         // Add Bearer token if API key is provided (prefer header over query param)
-        if let Some(key) = &api_key {
+        if let Some(key) = api_key {
             http_request = http_request.bearer_auth(key);
         }
 
         // Execute request
-        let response = http_request.send().await.map_err(|e| DatenoError::Http(e))?;
+        let response = http_request.send().await.map_err(RequestFailure::from_http)?;
 
         // Check status
         if !response.status().is_success() {
             let status = response.status();
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(parse_retry_after);
             let body = response.text().await.unwrap_or_default();
-            return Err(DatenoError::Api(format!(
-                "API returned status {}: {}",
-                status, body
-            )));
+            return Err(RequestFailure {
+                error: api_error(status.as_u16(), &body, retry_after),
+                retryable: matches!(status.as_u16(), 429 | 502 | 503 | 504),
+                retry_after,
+            });
         }
 
-        // Parse JSON response
-        let search_response: SearchResponse = response
-            .json()
-            .await
-            .map_err(|e| DatenoError::Json(e))?;
+        response.text().await.map_err(RequestFailure::from_http)
+    }
+}
+
+/// A failed request attempt, classified for the retry loop
+struct RequestFailure {
+    error: DatenoError,
+    retryable: bool,
+    /// Server-provided `Retry-After` delay, if any
+    retry_after: Option<Duration>,
+}
+
+impl RequestFailure {
+    /// Timeouts and dropped connections are safe to retry for idempotent GETs
+    fn from_http(error: reqwest::Error) -> Self {
+        Self {
+            retryable: error.is_timeout() || error.is_connect() || error.is_request(),
+            error: DatenoError::Http(error),
+            retry_after: None,
+        }
+    }
+}
+
+/// Maximum number of characters of a response body kept in error messages
+const MAX_ERROR_BODY_LEN: usize = 500;
+
+/// Map a non-success response to the matching structured error
+fn api_error(status: u16, body: &str, retry_after: Option<Duration>) -> DatenoError {
+    let truncated = truncate_body(body);
+    match status {
+        400 | 422 => DatenoError::BadRequest {
+            status,
+            message: parse_error_message(body).unwrap_or_else(|| truncated.clone()),
+            body: truncated,
+        },
+        401 => DatenoError::Unauthorized { status, body: truncated },
+        403 => DatenoError::Forbidden { status, body: truncated },
+        404 => DatenoError::NotFound { status, body: truncated },
+        429 => DatenoError::RateLimited {
+            status,
+            retry_after,
+            body: truncated,
+        },
+        500..=599 => DatenoError::ServerError { status, body: truncated },
+        _ => DatenoError::Api { status, body: truncated },
+    }
+}
+
+/// Shorten a response body for inclusion in error messages
+fn truncate_body(body: &str) -> String {
+    let body = body.trim();
+    match body.char_indices().nth(MAX_ERROR_BODY_LEN) {
+        Some((end, _)) => format!("{}...", &body[..end]),
+        None => body.to_string(),
+    }
+}
 
-        Ok(search_response)
+/// Extract a human-readable message from a JSON error payload such as
+/// `{"detail": "..."}`, `{"detail": [{"msg": "..."}]}`, `{"message": "..."}`
+/// or `{"error": "..."}`
+fn parse_error_message(body: &str) -> Option<String> {
+    let payload: serde_json::Value = serde_json::from_str(body).ok()?;
+    let message = match payload.get("detail") {
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(serde_json::Value::Array(items)) => items
+            .iter()
+            .filter_map(|item| item.get("msg").and_then(|m| m.as_str()))
+            .collect::<Vec<_>>()
+            .join("; "),
+        _ => ["message", "error"]
+            .iter()
+            .find_map(|key| payload.get(*key).and_then(|v| v.as_str()))?
+            .to_string(),
+    };
+    if message.is_empty() {
+        return None;
     }
+    Some(message)
 }
 
-/// Parse a filter string into (key, value) tuple
-fn parse_filter(filter: &str) -> Result<(String, String)> {
-    let parts: Vec<&str> = filter.splitn(2, '=').collect();
-    if parts.len() != 2 {
+/// Parse a `Retry-After` header given either as delay seconds or an HTTP date
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    let wait = date.with_timezone(&Utc) - Utc::now();
+    Some(wait.to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Parse a record-by-id response, keeping the original JSON alongside the
+/// typed fields so nothing the search schema omits is lost
+fn parse_entry(body: &str) -> Result<Dataset> {
+    decode_dataset(serde_json::from_str(body)?, true)
+}
+
+/// Parse a search response, attaching each result's original record when
+/// `request.keep_raw` is set and dropping undecodable ones when
+/// `request.skip_malformed` is set
+fn parse_search_response(body: &str, request: &SearchRequest) -> Result<SearchResponse> {
+    let response: RawSearchResponse = serde_json::from_str(body)?;
+    let mut results = Vec::with_capacity(response.results.len());
+    let mut skipped = 0;
+    for record in response.results {
+        match decode_dataset(record, request.keep_raw) {
+            Ok(dataset) => results.push(dataset),
+            Err(_) if request.skip_malformed => skipped += 1,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(SearchResponse {
+        total: response.total,
+        results,
+        facets: response.facets,
+        next_cursor: response.next_cursor,
+        skipped,
+    })
+}
+
+/// Decode one result record, noting timestamps that were present but unparseable
+fn decode_dataset(record: serde_json::Value, keep_raw: bool) -> Result<Dataset> {
+    let mut dataset = Dataset::deserialize(&record)?;
+
+    let mut timestamps = vec![
+        ("created_at".to_string(), record.get("created_at"), dataset.created_at),
+        ("updated_at".to_string(), record.get("updated_at"), dataset.updated_at),
+    ];
+    let raw_resources = record.get("resources").and_then(|r| r.as_array());
+    for (i, resource) in dataset.resources.iter().enumerate() {
+        let value = raw_resources.and_then(|r| r.get(i)).and_then(|r| r.get("last_modified"));
+        timestamps.push((format!("resources[{}].last_modified", i), value, resource.last_modified));
+    }
+    for (field, value, parsed) in timestamps {
+        match value {
+            Some(value) if !value.is_null() && parsed.is_none() => {
+                dataset.warnings.push(format!("{}: unrecognised timestamp {}", field, value));
+            }
+            _ => {}
+        }
+    }
+
+    if keep_raw {
+        dataset.raw = Some(record);
+    }
+    Ok(dataset)
+}
+
+/// Walks the API's pages for a single query.
+///
+/// Each call to [`SearchPager::next_page`] issues one request of at most
+/// [`MAX_PAGE_SIZE`] results, stopping once `max_results` rows have been
+/// fetched or the API's reported `total` is exhausted. Pages are addressed by
+/// offset, or by following `next_cursor` when the request starts from a cursor.
+///
+/// With [local filters](SearchPager::with_local_filters), results failing them
+/// are dropped and `max_results` counts only the results that pass. As few
+/// results may pass, such a walk fails after
+/// [`max_scan_pages`](SearchPager::with_max_scan_pages) requests rather than
+/// paging through the whole result set.
+pub struct SearchPager {
+    client: Arc<DatenoClient>,
+    request: SearchRequest,
+    max_results: Option<u64>,
+    local_filters: Vec<Filter>,
+    max_scan_pages: u32,
+    pages: u32,
+    fetched: u64,
+    returned: u64,
+    skipped: u64,
+    total: Option<usize>,
+    next_cursor: Option<String>,
+    done: bool,
+}
+
+impl SearchPager {
+    /// Create a pager starting at `request.offset`; `max_results` of `None` means no limit
+    pub fn new(client: Arc<DatenoClient>, request: SearchRequest, max_results: Option<u64>) -> Self {
+        Self {
+            client,
+            request,
+            max_results,
+            local_filters: Vec::new(),
+            max_scan_pages: DEFAULT_MAX_SCAN_PAGES,
+            pages: 0,
+            fetched: 0,
+            returned: 0,
+            skipped: 0,
+            total: None,
+            next_cursor: None,
+            done: false,
+        }
+    }
+
+    /// Check each result against `filters` (ANDed) and drop those that fail,
+    /// for predicates the API cannot evaluate itself
+    pub fn with_local_filters(mut self, filters: Vec<Filter>) -> Self {
+        self.local_filters = filters;
+        self
+    }
+
+    /// Limit how many pages are fetched while local filters are set
+    pub fn with_max_scan_pages(mut self, pages: u32) -> Self {
+        self.max_scan_pages = pages;
+        self
+    }
+
+    /// Total number of hits reported by the API, known after the first page
+    pub fn total(&self) -> Option<usize> {
+        self.total
+    }
+
+    /// Cursor for the page after the most recently fetched one, when the API
+    /// provides it and no result on that page was cut off by `max_results`
+    pub fn next_cursor(&self) -> Option<&str> {
+        self.next_cursor.as_deref()
+    }
+
+    /// Number of malformed results dropped so far, when the request sets
+    /// [`SearchRequest::skip_malformed`]
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+
+    /// Fetch the next page of results, or `None` once the result set is exhausted.
+    /// A page may be empty when every result on it was dropped as malformed or
+    /// by a local filter.
+    pub fn next_page(&mut self) -> Result<Option<Vec<Dataset>>> {
+        if self.done {
+            return Ok(None);
+        }
+
+        // The limit counts returned rows, so malformed or filtered-out results
+        // are made up by later pages. Without local filters most fetched results
+        // are returned, so the request asks for just what is left of the limit
+        let filtered = !self.local_filters.is_empty();
+        let page_size = match self.max_results.map(|max| max.saturating_sub(self.returned)) {
+            Some(0) => 0,
+            Some(_) if filtered => MAX_PAGE_SIZE,
+            Some(remaining) => remaining.min(MAX_PAGE_SIZE as u64) as i64,
+            None => MAX_PAGE_SIZE,
+        };
+        if page_size <= 0 {
+            self.done = true;
+            return Ok(None);
+        }
+        if filtered && self.pages >= self.max_scan_pages {
+            return Err(DatenoError::ScanLimit { pages: self.pages });
+        }
+        self.pages += 1;
+
+        let offset = self.request.offset + self.fetched as i64;
+        let page_request = SearchRequest {
+            limit: page_size,
+            offset,
+            ..self.request.clone()
+        };
+        let response = self.client.search(page_request)?;
+
+        // Dropped results still advance the offset
+        let received = response.results.len() + response.skipped;
+        self.fetched += received as u64;
+        let mut results = response.results;
+        if filtered {
+            results.retain(|dataset| self.local_filters.iter().all(|f| f.matches(dataset)));
+        }
+        let kept = results.len();
+        if let Some(max) = self.max_results {
+            results.truncate((max - self.returned) as usize);
+        }
+        self.returned += results.len() as u64;
+        self.skipped += response.skipped as u64;
+        self.total = Some(response.total);
+
+        // A short page means the API has nothing further to return; in cursor
+        // mode so does a missing next cursor
+        let exhausted = match &self.request.cursor {
+            Some(_) => response.next_cursor.is_none(),
+            None => offset as usize + received >= response.total,
+        };
+
+        // The cursor resumes after the whole page, so it would skip rows cut
+        // off by the limit; the walk ends here either way
+        self.next_cursor = if results.len() < kept {
+            None
+        } else {
+            response.next_cursor
+        };
+        let limit_reached = matches!(self.max_results, Some(max) if self.returned >= max);
+        if received < page_size as usize || exhausted || limit_reached {
+            self.done = true;
+        }
+        if self.request.cursor.is_some() {
+            self.request.cursor = self.next_cursor.clone();
+        }
+
+        if received == 0 {
+            return Ok(None);
+        }
+        Ok(Some(results))
+    }
+}
+
+/// Parse a filter string into (key, value) tuple.
+///
+/// The value is everything after the first `=`, so it may itself contain `=`
+/// and keeps its whitespace; the key must be a filter field the API supports,
+/// such as `source.countries.name`, and aliases are returned as the API's key
+/// (see [`filters::api_field`]).
+pub fn parse_filter(filter: &str) -> Result<(String, String)> {
+    let Some((key, value)) = filter.split_once('=') else {
         return Err(DatenoError::InvalidFilter(format!(
             "Filter must be in format 'key=value', got: {}",
             filter
         )));
+    };
+    let key = key.trim();
+    if !filters::is_known_field(key) {
+        return Err(DatenoError::InvalidFilter(format!(
+            "'{}' is not a known filter field such as 'format' or 'source.countries.name', in filter: {}",
+            key, filter
+        )));
+    }
+    let Some(key) = filters::api_field(key) else {
+        return Err(DatenoError::InvalidFilter(format!(
+            "the API cannot filter on '{}', in filter: {}",
+            key, filter
+        )));
+    };
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// Encode a filter as the API expects it, `"key"="value"`. Both sides are
+/// JSON string literals, so quotes and backslashes in the value are escaped
+/// rather than ending the string early.
+pub fn encode_filter(key: &str, value: &str) -> String {
+    format!("{}={}", serde_json::Value::from(key), serde_json::Value::from(value))
+}
+
+/// Inverse of [`encode_filter`]
+pub fn decode_filter(encoded: &str) -> Result<(String, String)> {
+    let invalid = || {
+        DatenoError::InvalidFilter(format!(
+            "Encoded filter must be in format '\"key\"=\"value\"', got: {}",
+            encoded
+        ))
+    };
+    let key_len = json_string_len(encoded).ok_or_else(invalid)?;
+    let (key, value) = encoded.split_at(key_len);
+    let value = value.strip_prefix('=').ok_or_else(invalid)?;
+    let key: String = serde_json::from_str(key).map_err(|_| invalid())?;
+    let value: String = serde_json::from_str(value).map_err(|_| invalid())?;
+    Ok((key, value))
+}
+
+/// Byte length of the JSON string literal at the start of `s`, if it has one
+fn json_string_len(s: &str) -> Option<usize> {
+    let mut chars = s.char_indices();
+    if chars.next()?.1 != '"' {
+        return None;
+    }
+    let mut escaped = false;
+    for (i, c) in chars {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return Some(i + 1),
+            _ => {}
+        }
     }
-    Ok((parts[0].to_string(), parts[1].to_string()))
+    None
 }
 
 #[cfg(test)]
@@ -180,11 +1176,274 @@ mod tests {
         let (key, value) = parse_filter("source.countries.name=Canada").unwrap();
         assert_eq!(key, "source.countries.name");
         assert_eq!(value, "Canada");
+
+        // Aliases are sent under the API's key
+        let (key, _) = parse_filter("source.languages.id=FR").unwrap();
+        assert_eq!(key, "source.langs.id");
+    }
+
+    #[test]
+    fn test_base_url_keeps_path_prefix() {
+        let client = DatenoClient::new("http://localhost:8080/mirror", None).unwrap();
+        let endpoint = client.base_url.join(SEARCH_PATH).unwrap();
+        assert_eq!(endpoint.as_str(), "http://localhost:8080/mirror/search/0.2/query");
+    }
+
+    #[test]
+    fn test_shared_clients_are_pooled_by_configuration() {
+        let shared = |api_key: Option<&str>| {
+            DatenoClient::shared(
+                "http://localhost:1",
+                api_key.map(String::from),
+                RetryPolicy::default(),
+                CachePolicy::default(),
+            )
+            .unwrap()
+        };
+        let a = shared(None);
+        let b = shared(None);
+        let c = shared(Some("key"));
+
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &c));
+        assert!(Arc::ptr_eq(&a.runtime, &c.runtime));
+    }
+
+    #[test]
+    fn test_parse_entry_keeps_raw_record() {
+        let dataset = parse_entry(r#"{"id": "d1", "title": "T", "license": "CC-BY"}"#).unwrap();
+        assert_eq!(dataset.id, "d1");
+        assert_eq!(dataset.raw.unwrap()["license"], "CC-BY");
+    }
+
+    #[test]
+    fn test_parse_search_response_keeps_raw_records() {
+        let body = r#"{"total": 1, "results": [{"id": "d1", "title": "T", "spatial": {"bbox": [1, 2, 3, 4]}}]}"#;
+
+        let request = SearchRequest {
+            keep_raw: true,
+            ..Default::default()
+        };
+        let response = parse_search_response(body, &request).unwrap();
+        assert_eq!(response.results[0].raw.as_ref().unwrap()["spatial"]["bbox"][2], 3);
+
+        let response = parse_search_response(body, &SearchRequest::default()).unwrap();
+        assert!(response.results[0].raw.is_none());
+    }
+
+    #[test]
+    fn test_unparseable_timestamps_become_warnings() {
+        let body = r#"{"total": 2, "results": [
+            {"id": "d1", "title": "T", "created_at": "2021-05-03", "updated_at": "last tuesday",
+             "resources": [{"url": "u", "last_modified": "n/a"}]},
+            {"id": "d2", "title": "T", "created_at": 1620000000, "updated_at": null}
+        ]}"#;
+
+        let response = parse_search_response(body, &SearchRequest::default()).unwrap();
+        let first = &response.results[0];
+        assert!(first.created_at.is_some());
+        assert!(first.updated_at.is_none());
+        assert_eq!(
+            first.warnings,
+            vec![
+                r#"updated_at: unrecognised timestamp "last tuesday""#,
+                r#"resources[0].last_modified: unrecognised timestamp "n/a""#,
+            ]
+        );
+
+        let second = &response.results[1];
+        assert!(second.created_at.is_some());
+        assert!(second.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_skip_malformed_results() {
+        let body = r#"{"total": 3, "results": [
+            {"id": "d1", "title": "T"},
+            {"id": "d2"},
+            {"id": "d3", "title": "T", "formats": "CSV"}
+        ]}"#;
+
+        assert!(parse_search_response(body, &SearchRequest::default()).is_err());
+
+        let request = SearchRequest {
+            skip_malformed: true,
+            ..Default::default()
+        };
+        let response = parse_search_response(body, &request).unwrap();
+        assert_eq!(response.results.len(), 1);
+        assert_eq!(response.results[0].id, "d1");
+        assert_eq!(response.skipped, 2);
+    }
+
+    #[test]
+    fn test_sort_order_parse() {
+        let param = |s: &str| SortOrder::parse(s).unwrap().to_param();
+        assert_eq!(param("updated_at desc"), Some("updated_at desc".to_string()));
+        assert_eq!(param("-created_at"), Some("created_at desc".to_string()));
+        assert_eq!(param(" Title "), Some("title asc".to_string()));
+        assert_eq!(param("relevance"), None);
+        assert_eq!(param("relevance desc"), None);
+
+        for invalid in ["", "downloads", "title sideways", "title asc extra", "relevance asc"] {
+            assert!(matches!(SortOrder::parse(invalid), Err(DatenoError::InvalidSort(_))), "{}", invalid);
+        }
+
+        let message = SortOrder::parse("downloads").unwrap_err().to_string();
+        assert!(message.contains(&SORT_FIELDS.join(", ")), "{}", message);
+    }
+
+    #[test]
+    fn test_source_deserialization() {
+        let dataset: Dataset = serde_json::from_str(
+            r#"{"id": "d1", "title": "T", "source": {
+                "name": "Open Canada", "url": "https://open.canada.ca",
+                "software": {"id": "ckan", "name": "CKAN"},
+                "countries": [{"id": "CA", "name": "Canada"}],
+                "langs": [{"id": "EN", "name": "English"}, {"id": "FR", "name": "French"}],
+                "organizations": [{"name": "Government of Canada"}]
+            }}"#,
+        )
+        .unwrap();
+        assert_eq!(dataset.source.catalog_name, "Open Canada");
+        assert_eq!(dataset.source.catalog_url, "https://open.canada.ca");
+        assert_eq!(dataset.source.software, "CKAN");
+        assert_eq!(dataset.source.countries[0].id, "CA");
+        assert_eq!(dataset.source.languages.len(), 2);
+        assert_eq!(dataset.source.organizations[0].name, "Government of Canada");
+
+        // Older responses flatten the source to its catalog name
+        let dataset: Dataset = serde_json::from_str(r#"{"id": "d1", "title": "T", "source": "NOAA"}"#).unwrap();
+        assert_eq!(dataset.source.catalog_name, "NOAA");
+        assert!(dataset.source.countries.is_empty());
+    }
+
+    #[test]
+    fn test_resource_deserialization() {
+        let dataset: Dataset = serde_json::from_str(
+            r#"{"id": "d1", "title": "T", "resources": [
+                {"name": "data.csv", "url": "https://example.org/data.csv", "format": "CSV",
+                 "mimetype": "text/csv", "size": 1024, "last_modified": "2024-01-02T03:04:05Z"},
+                {"url": "https://example.org/data.json", "size": "2048"},
+                {"url": "https://example.org/data.xml", "size": "unknown"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(dataset.resources.len(), 3);
+        assert_eq!(dataset.resources[0].mimetype, "text/csv");
+        assert_eq!(dataset.resources[0].size, Some(1024));
+        assert!(dataset.resources[0].last_modified.is_some());
+        assert_eq!(dataset.resources[1].size, Some(2048));
+        assert_eq!(dataset.resources[2].size, None);
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    #[test]
+    fn test_retry_delay_backoff() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(350),
+            jitter: false,
+        };
+        assert_eq!(policy.delay(1, None), Duration::from_millis(100));
+        assert_eq!(policy.delay(2, None), Duration::from_millis(200));
+        assert_eq!(policy.delay(3, None), Duration::from_millis(350));
+        assert_eq!(
+            policy.delay(1, Some(Duration::from_secs(10))),
+            Duration::from_millis(350)
+        );
+    }
+
+    #[test]
+    fn test_api_error_variants() {
+        assert!(matches!(api_error(401, "", None), DatenoError::Unauthorized { status: 401, .. }));
+        assert!(matches!(api_error(404, "", None), DatenoError::NotFound { .. }));
+        assert!(matches!(
+            api_error(429, "", Some(Duration::from_secs(5))),
+            DatenoError::RateLimited { retry_after: Some(_), .. }
+        ));
+        assert!(matches!(api_error(503, "", None), DatenoError::ServerError { status: 503, .. }));
+        assert!(matches!(api_error(418, "", None), DatenoError::Api { status: 418, .. }));
+        assert!(api_error(401, "", None).to_string().contains("DATENO_API_KEY"));
+    }
+
+    #[test]
+    fn test_api_error_parses_bad_request_payload() {
+        let err = api_error(422, r#"{"detail": [{"msg": "limit too large"}]}"#, None);
+        match err {
+            DatenoError::BadRequest { message, .. } => assert_eq!(message, "limit too large"),
+            other => panic!("unexpected error: {:?}", other),
+        }
+
+        let err = api_error(400, r#"{"message": "bad filter"}"#, None);
+        assert!(err.to_string().contains("bad filter"));
+    }
+
+    #[test]
+    fn test_truncate_body() {
+        let long = "é".repeat(MAX_ERROR_BODY_LEN + 10);
+        let truncated = truncate_body(&long);
+        assert_eq!(truncated.chars().count(), MAX_ERROR_BODY_LEN + 3);
+        assert_eq!(truncate_body("  short  "), "short");
     }
 
     #[test]
     fn test_parse_filter_invalid() {
         let result = parse_filter("invalid");
         assert!(result.is_err());
+
+        for filter in ["=CSV", "source..name=x", "1st=x", "source name=x", "\"format\"=CSV", "formt=CSV", "title=x"] {
+            assert!(
+                matches!(parse_filter(filter), Err(DatenoError::InvalidFilter(_))),
+                "{:?} should be rejected",
+                filter
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_filter_keeps_value_verbatim() {
+        let (key, value) = parse_filter(" source.organizations.name = a=b ").unwrap();
+        assert_eq!(key, "source.organizations.name");
+        assert_eq!(value, " a=b ");
+    }
+
+    #[test]
+    fn test_encode_filter_escapes_and_round_trips() {
+        assert_eq!(encode_filter("format", "CSV"), r#""format"="CSV""#);
+        assert_eq!(
+            encode_filter("source.organizations.name", r#"Bureau "Stats" \ Co"#),
+            r#""source.organizations.name"="Bureau \"Stats\" \\ Co""#
+        );
+
+        let values = [
+            r#"Bureau "Stats""#,
+            r#"ends with \"#,
+            "Québec — 東京",
+            "  padded\tand\nmultiline  ",
+            "a=b",
+            r#"","x"="y"#,
+            "",
+        ];
+        for value in values {
+            let encoded = encode_filter("source.organizations.name", value);
+            let (key, decoded) = decode_filter(&encoded).unwrap();
+            assert_eq!(key, "source.organizations.name");
+            assert_eq!(decoded, value, "round trip of {}", encoded);
+        }
+
+        assert!(decode_filter(r#""format"="CSV"x"#).is_err());
+        assert!(decode_filter(r#""format"CSV"#).is_err());
+        assert!(decode_filter("format=CSV").is_err());
     }
 }
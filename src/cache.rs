@@ -0,0 +1,372 @@
+use crate::error::{DatenoError, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// How a client caches API responses
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CachePolicy {
+    /// How long a cached response stays valid; zero disables the cache
+    pub ttl: Duration,
+    /// Most responses kept in memory; least recently used ones are evicted first
+    pub max_entries: usize,
+    /// Persistent cache directory, consulted in addition to the in-memory cache
+    pub disk: Option<DiskCache>,
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(300),
+            max_entries: 256,
+            disk: None,
+        }
+    }
+}
+
+impl CachePolicy {
+    /// Policy that never caches
+    pub fn none() -> Self {
+        Self {
+            ttl: Duration::ZERO,
+            ..Default::default()
+        }
+    }
+
+    /// Whether responses are kept in the in-memory cache
+    pub fn memory_enabled(&self) -> bool {
+        !self.ttl.is_zero() && self.max_entries > 0
+    }
+
+    /// The on-disk cache, unless none is configured or its mode is `off`
+    pub fn active_disk(&self) -> Option<&DiskCache> {
+        self.disk.as_ref().filter(|disk| disk.mode != DiskCacheMode::Off)
+    }
+}
+
+/// How the on-disk cache is used
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DiskCacheMode {
+    /// Ignore the cache directory
+    Off,
+    /// Answer from disk when possible, store every new response
+    #[default]
+    ReadWrite,
+    /// Answer only from disk; a miss is an error and the network is never used
+    ReadOnly,
+    /// Always fetch from the API and overwrite what is on disk
+    Refresh,
+}
+
+impl DiskCacheMode {
+    pub const NAMES: [&'static str; 4] = ["off", "read_write", "read_only", "refresh"];
+
+    /// Parse a `DATENO_CACHE_MODE` value
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "off" => Some(DiskCacheMode::Off),
+            "read_write" => Some(DiskCacheMode::ReadWrite),
+            "read_only" => Some(DiskCacheMode::ReadOnly),
+            "refresh" => Some(DiskCacheMode::Refresh),
+            _ => None,
+        }
+    }
+
+    /// Whether stored responses may answer a request
+    pub fn reads(self) -> bool {
+        matches!(self, DiskCacheMode::ReadWrite | DiskCacheMode::ReadOnly)
+    }
+
+    /// Whether fetched responses are written to disk
+    pub fn writes(self) -> bool {
+        matches!(self, DiskCacheMode::ReadWrite | DiskCacheMode::Refresh)
+    }
+}
+
+/// A directory of request/response pairs, one JSON file per request.
+///
+/// Entries never expire: the point is to replay the exact responses an
+/// analysis was written against, including offline.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DiskCache {
+    pub dir: PathBuf,
+    pub mode: DiskCacheMode,
+}
+
+/// On-disk representation of a cached response
+#[derive(Serialize, Deserialize)]
+struct DiskEntry {
+    url: String,
+    auth: Option<u64>,
+    stored_at: String,
+    body: String,
+}
+
+impl DiskCache {
+    /// Read the stored response for `key`, if any
+    pub fn load(&self, key: &CacheKey) -> Result<Option<String>> {
+        let path = self.path(key);
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(io_error(&path, e)),
+        };
+        let entry: DiskEntry = serde_json::from_str(&contents)?;
+
+        // Guard against hash collisions
+        if entry.url != key.url || entry.auth != key.auth {
+            return Ok(None);
+        }
+        Ok(Some(entry.body))
+    }
+
+    /// Write the response for `key`, replacing any earlier one
+    pub fn store(&self, key: &CacheKey, body: &str) -> Result<()> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| io_error(&self.dir, e))?;
+
+        let entry = DiskEntry {
+            url: key.url.clone(),
+            auth: key.auth,
+            stored_at: Utc::now().to_rfc3339(),
+            body: body.to_string(),
+        };
+        let path = self.path(key);
+
+        // Write then rename so readers never see a partial file
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec(&entry)?).map_err(|e| io_error(&tmp, e))?;
+        std::fs::rename(&tmp, &path).map_err(|e| io_error(&path, e))
+    }
+
+    fn path(&self, key: &CacheKey) -> PathBuf {
+        let mut id = key.url.clone();
+        if let Some(auth) = key.auth {
+            id.push_str(&format!("\n{:016x}", auth));
+        }
+        self.dir.join(format!("{:016x}.json", fnv1a(id.as_bytes())))
+    }
+}
+
+fn io_error(path: &std::path::Path, source: std::io::Error) -> DatenoError {
+    DatenoError::CacheIo {
+        path: path.display().to_string(),
+        source,
+    }
+}
+
+/// 64-bit FNV-1a. Unlike `DefaultHasher` it is stable across Rust releases,
+/// which on-disk file names need.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Identifies a cached response: the full request URL (base URL, path, query,
+/// filters, paging, fields) plus the identity the request was made as
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    url: String,
+    /// Hash of the API key, so responses are never shared across credentials
+    /// and the key itself is not kept around
+    auth: Option<u64>,
+}
+
+impl CacheKey {
+    pub fn new(url: &str, api_key: Option<&str>) -> Self {
+        Self {
+            url: url.to_string(),
+            auth: api_key.map(|key| fnv1a(key.as_bytes())),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+/// Counters reported by `dateno_cache_stats()`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub entries: usize,
+    /// Total size of the cached response bodies
+    pub bytes: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+struct CacheEntry {
+    body: String,
+    stored_at: Instant,
+    /// Value of the cache's use counter when this entry was last read or written
+    last_used: u64,
+}
+
+/// In-memory LRU cache of response bodies with a per-lookup TTL.
+///
+/// Eviction scans for the least recently used entry, which is cheap at the
+/// sizes this cache is meant for (hundreds of entries).
+#[derive(Default)]
+pub struct ResponseCache {
+    entries: HashMap<CacheKey, CacheEntry>,
+    clock: u64,
+    stats: CacheStats,
+}
+
+impl ResponseCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Look up a response stored less than `ttl` ago
+    pub fn get(&mut self, key: &CacheKey, ttl: Duration) -> Option<String> {
+        self.clock += 1;
+        let fresh = match self.entries.get_mut(key) {
+            Some(entry) if entry.stored_at.elapsed() < ttl => {
+                entry.last_used = self.clock;
+                Some(entry.body.clone())
+            }
+            Some(_) => {
+                self.remove(key);
+                None
+            }
+            None => None,
+        };
+        match fresh {
+            Some(_) => self.stats.hits += 1,
+            None => self.stats.misses += 1,
+        }
+        fresh
+    }
+
+    /// Store a response, evicting least recently used entries to stay within `max_entries`
+    pub fn insert(&mut self, key: CacheKey, body: String, max_entries: usize) {
+        self.clock += 1;
+        self.remove(&key);
+        while !self.entries.is_empty() && self.entries.len() >= max_entries {
+            self.evict_lru();
+        }
+        if max_entries == 0 {
+            return;
+        }
+        self.stats.bytes += body.len();
+        self.entries.insert(
+            key,
+            CacheEntry {
+                body,
+                stored_at: Instant::now(),
+                last_used: self.clock,
+            },
+        );
+    }
+
+    /// Drop every entry, returning how many there were. Hit and miss counters are kept.
+    pub fn clear(&mut self) -> usize {
+        let cleared = self.entries.len();
+        self.entries.clear();
+        self.stats.bytes = 0;
+        cleared
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.entries.len(),
+            ..self.stats
+        }
+    }
+
+    fn remove(&mut self, key: &CacheKey) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.stats.bytes -= entry.body.len();
+        }
+    }
+
+    fn evict_lru(&mut self) {
+        let oldest = self
+            .entries
+            .iter()
+            .min_by_key(|(_, entry)| entry.last_used)
+            .map(|(key, _)| key.clone());
+        if let Some(key) = oldest {
+            self.remove(&key);
+            self.stats.evictions += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    fn key(url: &str) -> CacheKey {
+        CacheKey::new(url, None)
+    }
+
+    #[test]
+    fn test_get_respects_ttl_and_auth() {
+        let mut cache = ResponseCache::new();
+        cache.insert(CacheKey::new("u", Some("k1")), "body".to_string(), 10);
+
+        assert_eq!(cache.get(&CacheKey::new("u", Some("k1")), TTL).as_deref(), Some("body"));
+        assert_eq!(cache.get(&CacheKey::new("u", Some("k2")), TTL), None);
+        assert_eq!(cache.get(&key("u"), TTL), None);
+
+        // An expired entry is dropped on lookup
+        assert_eq!(cache.get(&CacheKey::new("u", Some("k1")), Duration::ZERO), None);
+        assert_eq!(cache.stats().entries, 0);
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (1, 3));
+    }
+
+    #[test]
+    fn test_disk_cache_round_trip() {
+        let dir = std::env::temp_dir().join(format!("dateno-cache-test-{}", std::process::id()));
+        let disk = DiskCache {
+            dir: dir.clone(),
+            mode: DiskCacheMode::ReadWrite,
+        };
+
+        assert_eq!(disk.load(&key("u")).unwrap(), None);
+        disk.store(&key("u"), "body").unwrap();
+        assert_eq!(disk.load(&key("u")).unwrap().as_deref(), Some("body"));
+        assert_eq!(disk.load(&CacheKey::new("u", Some("k"))).unwrap(), None);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_disk_cache_mode_parse() {
+        assert_eq!(DiskCacheMode::parse("READ_ONLY"), Some(DiskCacheMode::ReadOnly));
+        assert_eq!(DiskCacheMode::parse("refresh"), Some(DiskCacheMode::Refresh));
+        assert_eq!(DiskCacheMode::parse("sometimes"), None);
+        assert!(DiskCacheMode::ReadWrite.reads() && DiskCacheMode::ReadWrite.writes());
+        assert!(!DiskCacheMode::ReadOnly.writes() && !DiskCacheMode::Refresh.reads());
+    }
+
+    #[test]
+    fn test_insert_evicts_least_recently_used() {
+        let mut cache = ResponseCache::new();
+        cache.insert(key("a"), "1".to_string(), 2);
+        cache.insert(key("b"), "22".to_string(), 2);
+        cache.get(&key("a"), TTL);
+        cache.insert(key("c"), "333".to_string(), 2);
+
+        assert!(cache.get(&key("a"), TTL).is_some());
+        assert!(cache.get(&key("b"), TTL).is_none());
+        assert!(cache.get(&key("c"), TTL).is_some());
+
+        let stats = cache.stats();
+        assert_eq!(stats.entries, 2);
+        assert_eq!(stats.bytes, 4);
+        assert_eq!(stats.evictions, 1);
+
+        assert_eq!(cache.clear(), 2);
+        assert_eq!(cache.stats().bytes, 0);
+    }
+}
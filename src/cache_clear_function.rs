@@ -0,0 +1,56 @@
+use crate::api;
+use crate::table_function::{emit_once, DatenoTableFunction};
+use duckdb::{
+    core::{DataChunkHandle, LogicalTypeHandle, LogicalTypeId},
+    vtab::{BindInfo, InitInfo, TableFunctionInfo, VTab},
+};
+use std::error::Error;
+use std::sync::Mutex;
+
+/// Bind data for `dateno_cache_clear` (takes no parameters)
+#[derive(Clone)]
+pub struct DatenoCacheClearBindData;
+
+/// Init data for `dateno_cache_clear`; the cache is cleared in init and the
+/// number of removed entries emitted by the first `func` call
+pub struct DatenoCacheClearInitData {
+    pub cleared: Mutex<Option<usize>>,
+}
+
+/// `dateno_cache_clear()`: drop every cached API response
+pub struct DatenoCacheClearFunction;
+
+impl VTab for DatenoCacheClearFunction {
+    type InitData = DatenoCacheClearInitData;
+    type BindData = DatenoCacheClearBindData;
+
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        Self::add_result_columns(bind, &DatenoCacheClearBindData);
+        Ok(DatenoCacheClearBindData)
+    }
+
+    fn init(_init: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        Ok(DatenoCacheClearInitData {
+            cleared: Mutex::new(Some(api::clear_cache())),
+        })
+    }
+
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init_data = func.get_init_data();
+
+        emit_once(&init_data.cleared, output, |cleared, output| {
+            output.flat_vector(0).as_mut_slice::<i64>()[0] = cleared as i64;
+        })
+    }
+}
+
+impl DatenoTableFunction for DatenoCacheClearFunction {
+    const NAME: &'static str = "dateno_cache_clear";
+
+    fn result_columns(_bind_data: &Self::BindData) -> Vec<(&'static str, LogicalTypeHandle)> {
+        vec![("cleared", LogicalTypeId::Bigint.into())]
+    }
+}
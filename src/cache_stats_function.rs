@@ -0,0 +1,72 @@
+use crate::api;
+use crate::cache::CacheStats;
+use crate::table_function::{emit_once, DatenoTableFunction};
+use duckdb::{
+    core::{DataChunkHandle, LogicalTypeHandle, LogicalTypeId},
+    vtab::{BindInfo, InitInfo, TableFunctionInfo, VTab},
+};
+use std::error::Error;
+use std::sync::Mutex;
+
+/// Bind data for `dateno_cache_stats` (takes no parameters)
+#[derive(Clone)]
+pub struct DatenoCacheStatsBindData;
+
+/// Init data for `dateno_cache_stats`; the counters are snapshotted in init
+/// and emitted by the first `func` call
+pub struct DatenoCacheStatsInitData {
+    pub stats: Mutex<Option<CacheStats>>,
+}
+
+/// `dateno_cache_stats()`: size and hit counters of the response cache
+pub struct DatenoCacheStatsFunction;
+
+impl VTab for DatenoCacheStatsFunction {
+    type InitData = DatenoCacheStatsInitData;
+    type BindData = DatenoCacheStatsBindData;
+
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        Self::add_result_columns(bind, &DatenoCacheStatsBindData);
+        Ok(DatenoCacheStatsBindData)
+    }
+
+    fn init(_init: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        Ok(DatenoCacheStatsInitData {
+            stats: Mutex::new(Some(api::cache_stats())),
+        })
+    }
+
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init_data = func.get_init_data();
+
+        emit_once(&init_data.stats, output, |stats, output| {
+            let values = [
+                stats.entries as i64,
+                stats.bytes as i64,
+                stats.hits as i64,
+                stats.misses as i64,
+                stats.evictions as i64,
+            ];
+            for (column, value) in values.into_iter().enumerate() {
+                output.flat_vector(column).as_mut_slice::<i64>()[0] = value;
+            }
+        })
+    }
+}
+
+impl DatenoTableFunction for DatenoCacheStatsFunction {
+    const NAME: &'static str = "dateno_cache_stats";
+
+    fn result_columns(_bind_data: &Self::BindData) -> Vec<(&'static str, LogicalTypeHandle)> {
+        vec![
+            ("entries", LogicalTypeId::Bigint.into()),
+            ("bytes", LogicalTypeId::Bigint.into()),
+            ("hits", LogicalTypeId::Bigint.into()),
+            ("misses", LogicalTypeId::Bigint.into()),
+            ("evictions", LogicalTypeId::Bigint.into()),
+        ]
+    }
+}
@@ -0,0 +1,97 @@
+use crate::filters;
+use crate::table_function::{
+    api_filters, emit_once, get_parameter, init_bind_data, query_parameter, ClientConfig, DatenoTableFunction,
+};
+use duckdb::{
+    core::{DataChunkHandle, LogicalTypeHandle, LogicalTypeId},
+    vtab::{BindInfo, InitInfo, TableFunctionInfo, VTab},
+};
+use std::error::Error;
+use std::sync::Mutex;
+
+/// Bind data for `dateno_count` (read-only, shared across threads)
+#[derive(Clone)]
+pub struct DatenoCountBindData {
+    pub client_config: ClientConfig,
+    pub query: String,
+    pub filters: Vec<String>,
+}
+
+/// Init data for `dateno_count`; the total is emitted by the first `func` call
+pub struct DatenoCountInitData {
+    pub total: Mutex<Option<usize>>,
+}
+
+/// `dateno_count(query, filters)`: number of matching datasets, fetched with a zero-row request
+pub struct DatenoCountFunction;
+
+impl VTab for DatenoCountFunction {
+    type InitData = DatenoCountInitData;
+    type BindData = DatenoCountBindData;
+
+    fn parameters() -> Option<Vec<LogicalTypeHandle>> {
+        Some(vec![
+            LogicalTypeId::Varchar.into(),                              // query (required)
+            LogicalTypeId::Any.into(),                                  // filters (optional): LIST, MAP or STRUCT
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalTypeHandle)>> {
+        Some(vec![("filters".to_string(), LogicalTypeId::Any.into())])
+    }
+
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        let query = query_parameter(bind)?;
+
+        let filters = match filters::from_value(get_parameter(bind, 1, "filters"))
+            .map_err(|e| e.to_string())
+            .and_then(|filters| api_filters(Self::NAME, &filters))
+        {
+            Ok(filters) => filters,
+            Err(e) => {
+                bind.set_error(&e);
+                return Err(e.into());
+            }
+        };
+
+        let bind_data = DatenoCountBindData {
+            client_config: ClientConfig::resolve()?,
+            query,
+            filters,
+        };
+        Self::add_result_columns(bind, &bind_data);
+        Ok(bind_data)
+    }
+
+    fn init(init: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        let bind_data = init_bind_data::<DatenoCountBindData>(init)?;
+
+        let client = bind_data.client_config.client()?;
+        let total = client
+            .count(&bind_data.query, &bind_data.filters)
+            .map_err(|e| format!("Count failed: {}", e))?;
+
+        Ok(DatenoCountInitData {
+            total: Mutex::new(Some(total)),
+        })
+    }
+
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init_data = func.get_init_data();
+
+        emit_once(&init_data.total, output, |total, output| {
+            output.flat_vector(0).as_mut_slice::<i64>()[0] = total as i64;
+        })
+    }
+}
+
+impl DatenoTableFunction for DatenoCountFunction {
+    const NAME: &'static str = "dateno_count";
+
+    fn result_columns(_bind_data: &Self::BindData) -> Vec<(&'static str, LogicalTypeHandle)> {
+        vec![("count", LogicalTypeId::Bigint.into())]
+    }
+}
@@ -1,3 +1,4 @@
+use std::time::Duration;
 use thiserror::Error;
 
 /// Extension error types
@@ -18,11 +19,60 @@ pub enum DatenoError {
     #[error("Invalid limit: {0}. Must be between 1 and 100")]
     InvalidLimit(i64),
 
+    #[error("Invalid sort '{0}'. Use one of: {fields}; optionally followed by asc or desc (e.g. 'updated_at desc')", fields = crate::api::SORT_FIELDS.join(", "))]
+    InvalidSort(String),
+
     #[error("Missing required parameter: {0}")]
     MissingParameter(String),
 
-    #[error("API error: {0}")]
-    Api(String),
+    #[error("Unauthorized (HTTP {status}): {body}. Set DATENO_API_KEY")]
+    Unauthorized { status: u16, body: String },
+
+    #[error("Forbidden (HTTP {status}): {body}. The API key is valid but lacks access; check your Dateno plan or key permissions")]
+    Forbidden { status: u16, body: String },
+
+    #[error("Rate limited (HTTP {status}){}: {body}. Reduce request volume or raise DATENO_RETRY_MAX_ATTEMPTS", retry_hint(retry_after))]
+    RateLimited {
+        status: u16,
+        retry_after: Option<Duration>,
+        body: String,
+    },
+
+    #[error("Not found (HTTP {status}): {body}. Check the dataset id or DATENO_BASE_URL")]
+    NotFound { status: u16, body: String },
+
+    #[error("Bad request (HTTP {status}): {message}. Check the query, filters and parameters")]
+    BadRequest {
+        status: u16,
+        message: String,
+        body: String,
+    },
+
+    #[error("Dateno server error (HTTP {status}): {body}. The service may be unavailable; try again later")]
+    ServerError { status: u16, body: String },
+
+    #[error("API returned status {status}: {body}")]
+    Api { status: u16, body: String },
+
+    #[error("{source} (gave up after {attempts} attempts)")]
+    RetriesExhausted {
+        attempts: u32,
+        #[source]
+        source: Box<DatenoError>,
+    },
+
+    #[error("Fetched {pages} pages without enough results passing the filters the extension checks itself. Use = filters the API can apply, narrow the query or raise DATENO_MAX_SCAN_PAGES")]
+    ScanLimit { pages: u32 },
+
+    #[error("No cached response for {url} in {dir}. DATENO_CACHE_MODE is 'read_only'; switch to 'read_write' to fetch it")]
+    CacheMiss { url: String, dir: String },
+
+    #[error("Response cache error at {path}: {source}. Check DATENO_CACHE_DIR exists and is writable")]
+    CacheIo {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
 
     #[error("DuckDB error: {0}")]
     DuckDB(String),
@@ -31,4 +81,28 @@ pub enum DatenoError {
     Runtime(String),
 }
 
+impl DatenoError {
+    /// HTTP status code for errors returned by the API
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            DatenoError::Unauthorized { status, .. }
+            | DatenoError::Forbidden { status, .. }
+            | DatenoError::RateLimited { status, .. }
+            | DatenoError::NotFound { status, .. }
+            | DatenoError::BadRequest { status, .. }
+            | DatenoError::ServerError { status, .. }
+            | DatenoError::Api { status, .. } => Some(*status),
+            DatenoError::RetriesExhausted { source, .. } => source.status(),
+            _ => None,
+        }
+    }
+}
+
+fn retry_hint(retry_after: &Option<Duration>) -> String {
+    match retry_after {
+        Some(delay) => format!(", retry after {}s", delay.as_secs()),
+        None => String::new(),
+    }
+}
+
 pub type Result<T> = std::result::Result<T, DatenoError>;
@@ -0,0 +1,149 @@
+use crate::api::FacetCount;
+use crate::filters;
+use crate::table_function::{
+    api_filters, get_parameter, init_bind_data, positional_parameter, query_parameter, ClientConfig,
+    DatenoTableFunction, STANDARD_VECTOR_SIZE,
+};
+use duckdb::{
+    core::{DataChunkHandle, Inserter, LogicalTypeHandle, LogicalTypeId},
+    types::Value,
+    vtab::{BindInfo, InitInfo, TableFunctionInfo, VTab},
+};
+use std::error::Error;
+use std::sync::Mutex;
+
+/// Bind data for `dateno_facets` (read-only, shared across threads)
+#[derive(Clone)]
+pub struct DatenoFacetsBindData {
+    pub client_config: ClientConfig,
+    pub query: String,
+    pub facet_fields: Vec<String>,
+    pub filters: Vec<String>,
+}
+
+/// Init data for `dateno_facets`: every count is fetched up front
+pub struct DatenoFacetsInitData {
+    pub counts: Vec<FacetCount>,
+    pub current_index: Mutex<usize>,
+}
+
+/// `dateno_facets(query, facet_fields, filters)`: dataset counts per facet value
+pub struct DatenoFacetsFunction;
+
+/// Read a `LIST(VARCHAR)` parameter, skipping NULL entries
+fn string_list(value: Option<Value>) -> Vec<String> {
+    match value {
+        Some(Value::List(items)) => items
+            .into_iter()
+            .filter_map(|item| match item {
+                Value::Text(s) => Some(s),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+impl VTab for DatenoFacetsFunction {
+    type InitData = DatenoFacetsInitData;
+    type BindData = DatenoFacetsBindData;
+
+    fn parameters() -> Option<Vec<LogicalTypeHandle>> {
+        Some(vec![
+            LogicalTypeId::Varchar.into(),                           // query (required)
+            LogicalTypeHandle::list(&LogicalTypeId::Varchar.into()), // facet_fields (required)
+            LogicalTypeId::Any.into(),                               // filters (optional): LIST, MAP or STRUCT
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalTypeHandle)>> {
+        Some(vec![("filters".to_string(), LogicalTypeId::Any.into())])
+    }
+
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        if bind.get_parameter_count() < 2 {
+            bind.set_error("query and facet_fields parameters are required");
+            return Err("query and facet_fields parameters are required".into());
+        }
+
+        let query = query_parameter(bind)?;
+
+        let facet_fields = string_list(positional_parameter(bind, 1));
+        if facet_fields.is_empty() {
+            bind.set_error("facet_fields must list at least one field");
+            return Err("facet_fields must list at least one field".into());
+        }
+
+        let filters = match filters::from_value(get_parameter(bind, 2, "filters"))
+            .map_err(|e| e.to_string())
+            .and_then(|filters| api_filters(Self::NAME, &filters))
+        {
+            Ok(filters) => filters,
+            Err(e) => {
+                bind.set_error(&e);
+                return Err(e.into());
+            }
+        };
+
+        let bind_data = DatenoFacetsBindData {
+            client_config: ClientConfig::resolve()?,
+            query,
+            facet_fields,
+            filters,
+        };
+        Self::add_result_columns(bind, &bind_data);
+        Ok(bind_data)
+    }
+
+    fn init(init: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        let bind_data = init_bind_data::<DatenoFacetsBindData>(init)?;
+
+        let client = bind_data.client_config.client()?;
+        let counts = client
+            .facets(&bind_data.query, &bind_data.filters, &bind_data.facet_fields)
+            .map_err(|e| format!("Facet search failed: {}", e))?;
+
+        Ok(DatenoFacetsInitData {
+            counts,
+            current_index: Mutex::new(0),
+        })
+    }
+
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init_data = func.get_init_data();
+
+        let mut current_index = init_data
+            .current_index
+            .lock()
+            .map_err(|_| "Scan state lock poisoned")?;
+
+        let start = *current_index;
+        let end = (start + STANDARD_VECTOR_SIZE).min(init_data.counts.len());
+        for (row, count) in init_data.counts[start..end].iter().enumerate() {
+            output.flat_vector(0).insert(row, count.facet.as_str());
+            output.flat_vector(1).insert(row, count.value.as_str());
+            output.flat_vector(2).as_mut_slice::<i64>()[row] = count.count as i64;
+        }
+        *current_index = end;
+
+        // An empty chunk tells DuckDB the scan is complete
+        output.set_len(end - start);
+
+        Ok(())
+    }
+}
+
+impl DatenoTableFunction for DatenoFacetsFunction {
+    const NAME: &'static str = "dateno_facets";
+
+    fn result_columns(_bind_data: &Self::BindData) -> Vec<(&'static str, LogicalTypeHandle)> {
+        vec![
+            ("facet", LogicalTypeId::Varchar.into()),
+            ("value", LogicalTypeId::Varchar.into()),
+            ("count", LogicalTypeId::Bigint.into()),
+        ]
+    }
+}
@@ -0,0 +1,843 @@
+use crate::api::{Dataset, SourceEntity};
+use crate::error::{DatenoError, Result};
+use crate::timestamps;
+use chrono::{DateTime, Utc};
+use duckdb::types::Value;
+use std::fmt;
+
+/// Comparison operator of a filter predicate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CompareOp {
+    /// Operators in the order the parser tries them, longest first
+    const SYMBOLS: [(&'static str, CompareOp); 7] = [
+        ("!=", CompareOp::Ne),
+        ("<>", CompareOp::Ne),
+        (">=", CompareOp::Ge),
+        ("<=", CompareOp::Le),
+        ("=", CompareOp::Eq),
+        (">", CompareOp::Gt),
+        ("<", CompareOp::Lt),
+    ];
+
+    fn symbol(self) -> &'static str {
+        match self {
+            CompareOp::Eq => "=",
+            CompareOp::Ne => "!=",
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+            CompareOp::Gt => ">",
+            CompareOp::Ge => ">=",
+        }
+    }
+
+    fn holds(self, ordering: std::cmp::Ordering) -> bool {
+        use std::cmp::Ordering::*;
+        match self {
+            CompareOp::Eq => ordering == Equal,
+            CompareOp::Ne => ordering != Equal,
+            CompareOp::Lt => ordering == Less,
+            CompareOp::Le => ordering != Greater,
+            CompareOp::Gt => ordering == Greater,
+            CompareOp::Ge => ordering != Less,
+        }
+    }
+}
+
+/// A parsed filter expression.
+///
+/// Grammar (keywords are uppercase):
+///
+/// ```text
+/// expr      := and ( OR and )*
+/// and       := unary ( AND unary )*
+/// unary     := NOT unary | '(' expr ')' | predicate
+/// predicate := field op value | field [NOT] IN '(' value ( ',' value )* ')'
+/// op        := = | != | <> | < | <= | > | >=
+/// ```
+///
+/// Values are either quoted (`'...'` or `"..."`, with the quote doubled to
+/// escape it) or bare text running up to the next `AND`/`OR`. A `key=value`
+/// string that is not a valid expression and uses no keywords or parentheses,
+/// such as `title="Foo" bar`, is taken literally as before.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    Compare { field: String, op: CompareOp, value: String },
+    In { field: String, values: Vec<String> },
+    Not(Box<Filter>),
+    And(Vec<Filter>),
+    Or(Vec<Filter>),
+}
+
+impl Filter {
+    /// `field = value`
+    pub fn eq(field: &str, value: &str) -> Self {
+        Filter::Compare {
+            field: field.to_string(),
+            op: CompareOp::Eq,
+            value: value.to_string(),
+        }
+    }
+
+    /// Field paths the expression refers to
+    pub fn fields(&self) -> Vec<&str> {
+        match self {
+            Filter::Compare { field, .. } | Filter::In { field, .. } => vec![field.as_str()],
+            Filter::Not(inner) => inner.fields(),
+            Filter::And(terms) | Filter::Or(terms) => terms.iter().flat_map(Filter::fields).collect(),
+        }
+    }
+
+    /// Evaluate the expression against a decoded dataset. Multi-valued fields
+    /// (countries, formats, resources, ...) match when any of their values does;
+    /// a missing timestamp matches no comparison.
+    pub fn matches(&self, dataset: &Dataset) -> bool {
+        match self {
+            Filter::Compare { field, op, value } => match local_field(field) {
+                Some(LocalField::Text(get)) => match op {
+                    CompareOp::Ne => !get(dataset).contains(&value.as_str()),
+                    op => get(dataset).iter().any(|v| op.holds((*v).cmp(value.as_str()))),
+                },
+                Some(LocalField::Time(get)) => match (get(dataset), timestamps::parse(value)) {
+                    (Some(actual), Some(bound)) => op.holds(actual.cmp(&bound)),
+                    _ => false,
+                },
+                None => false,
+            },
+            Filter::In { field, values } => match local_field(field) {
+                Some(LocalField::Text(get)) => get(dataset).iter().any(|v| values.iter().any(|x| x == v)),
+                Some(LocalField::Time(get)) => get(dataset)
+                    .is_some_and(|actual| values.iter().any(|x| timestamps::parse(x) == Some(actual))),
+                None => false,
+            },
+            Filter::Not(inner) => !inner.matches(dataset),
+            Filter::And(terms) => terms.iter().all(|t| t.matches(dataset)),
+            Filter::Or(terms) => terms.iter().any(|t| t.matches(dataset)),
+        }
+    }
+
+    /// The field and value of an expression the API can evaluate itself: an
+    /// equality, or an `IN` list with a single value
+    fn api_equality(&self) -> Option<(&str, &str)> {
+        match self {
+            Filter::Compare {
+                field,
+                op: CompareOp::Eq,
+                value,
+            } => Some((field.as_str(), value.as_str())),
+            Filter::In { field, values } if values.len() == 1 => Some((field.as_str(), values[0].as_str())),
+            _ => None,
+        }
+    }
+
+    /// Check that every predicate can be evaluated locally
+    fn check_local(&self) -> Result<()> {
+        let (field, values) = match self {
+            Filter::Compare { field, value, .. } => (field, std::slice::from_ref(value)),
+            Filter::In { field, values } => (field, values.as_slice()),
+            Filter::Not(inner) => return inner.check_local(),
+            Filter::And(terms) | Filter::Or(terms) => return terms.iter().try_for_each(Filter::check_local),
+        };
+        match local_field(field) {
+            Some(LocalField::Text(_)) => Ok(()),
+            Some(LocalField::Time(_)) => match values.iter().find(|v| timestamps::parse(v).is_none()) {
+                Some(value) => Err(DatenoError::InvalidFilter(format!(
+                    "'{}' is not a date or timestamp, in filter: {}",
+                    value, self
+                ))),
+                None => Ok(()),
+            },
+            None => Err(DatenoError::InvalidFilter(format!(
+                "field '{}' only supports = filters combined with AND, got: {}",
+                field, self
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let quote = |value: &str| format!("'{}'", value.replace('\'', "''"));
+        match self {
+            Filter::Compare { field, op, value } => write!(f, "{} {} {}", field, op.symbol(), quote(value)),
+            Filter::In { field, values } => {
+                let values: Vec<String> = values.iter().map(|v| quote(v)).collect();
+                write!(f, "{} IN ({})", field, values.join(", "))
+            }
+            Filter::Not(inner) => write!(f, "NOT ({})", inner),
+            Filter::And(terms) | Filter::Or(terms) => {
+                let separator = if matches!(self, Filter::And(_)) { " AND " } else { " OR " };
+                let terms: Vec<String> = terms.iter().map(|t| format!("({})", t)).collect();
+                write!(f, "{}", terms.join(separator))
+            }
+        }
+    }
+}
+
+/// Filters split by where they are evaluated
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CompiledFilters {
+    /// `key=value` filters sent to the API
+    pub api: Vec<String>,
+    /// Predicates the API cannot express, checked against each result
+    pub local: Vec<Filter>,
+}
+
+/// Split ANDed filters into API filters and local predicates.
+///
+/// The API only understands equalities on its own filter keys, all of which
+/// must hold, so those are sent to it under the API's name for the field;
+/// equalities on other fields, `IN` lists with several values, `OR`s,
+/// negations and ranges are evaluated locally instead. Fails when a local
+/// predicate refers to a field the extension does not decode.
+pub fn compile(filters: &[Filter]) -> Result<CompiledFilters> {
+    let mut conjuncts = Vec::new();
+    flatten_and(filters, &mut conjuncts);
+
+    let mut compiled = CompiledFilters::default();
+    for filter in conjuncts {
+        match filter.api_equality().and_then(|(field, value)| Some((api_field(field)?, value))) {
+            Some((key, value)) => compiled.api.push(format!("{}={}", key, value)),
+            None => {
+                filter.check_local()?;
+                compiled.local.push(filter.clone());
+            }
+        }
+    }
+    Ok(compiled)
+}
+
+fn flatten_and<'a>(filters: &'a [Filter], out: &mut Vec<&'a Filter>) {
+    for filter in filters {
+        match filter {
+            Filter::And(terms) => flatten_and(terms, out),
+            filter => out.push(filter),
+        }
+    }
+}
+
+/// Top-level dataset field a filter path is read from, e.g. `source` for
+/// `source.countries.name`
+pub fn root_field(path: &str) -> &str {
+    path.split('.').next().unwrap_or(path)
+}
+
+/// Accessor for a field that can be filtered locally
+enum LocalField {
+    Text(fn(&Dataset) -> Vec<&str>),
+    Time(fn(&Dataset) -> Option<DateTime<Utc>>),
+}
+
+/// Whether `path` is a field filters can refer to. Every such field can be
+/// checked locally; only those with an [`api_field`] can be sent to the API.
+pub fn is_known_field(path: &str) -> bool {
+    local_field(path).is_some()
+}
+
+/// Filter keys the API understands
+const API_FIELDS: [&str; 12] = [
+    "id",
+    "format",
+    "updated_at",
+    "source.name",
+    "source.url",
+    "source.software",
+    "source.countries.id",
+    "source.countries.name",
+    "source.langs.id",
+    "source.langs.name",
+    "source.organizations.id",
+    "source.organizations.name",
+];
+
+/// The API's filter key for `path`, which may be one of the extension's
+/// aliases such as `source.languages.id`, or `None` when the API cannot
+/// filter on the field
+pub fn api_field(path: &str) -> Option<&'static str> {
+    let path = match path {
+        "source.catalog_name" => "source.name",
+        "source.catalog_url" => "source.url",
+        "source.languages.id" => "source.langs.id",
+        "source.languages.name" => "source.langs.name",
+        path => path,
+    };
+    API_FIELDS.iter().find(|key| **key == path).copied()
+}
+
+fn local_field(path: &str) -> Option<LocalField> {
+    use LocalField::{Text, Time};
+    let field = match path {
+        "id" => Text(|d| vec![d.id.as_str()]),
+        "title" => Text(|d| vec![d.title.as_str()]),
+        "description" => Text(|d| vec![d.description.as_str()]),
+        "format" => Text(|d| vec![d.format.as_str()]),
+        "url" => Text(|d| vec![d.url.as_str()]),
+        "formats" => Text(|d| d.formats.iter().map(String::as_str).collect()),
+        "created_at" => Time(|d| d.created_at),
+        "updated_at" => Time(|d| d.updated_at),
+        "source.name" | "source.catalog_name" => Text(|d| vec![d.source.catalog_name.as_str()]),
+        "source.url" | "source.catalog_url" => Text(|d| vec![d.source.catalog_url.as_str()]),
+        "source.software" => Text(|d| vec![d.source.software.as_str()]),
+        "source.countries.id" => Text(|d| entity_ids(&d.source.countries)),
+        "source.countries.name" => Text(|d| entity_names(&d.source.countries)),
+        "source.langs.id" | "source.languages.id" => Text(|d| entity_ids(&d.source.languages)),
+        "source.langs.name" | "source.languages.name" => Text(|d| entity_names(&d.source.languages)),
+        "source.organizations.id" => Text(|d| entity_ids(&d.source.organizations)),
+        "source.organizations.name" => Text(|d| entity_names(&d.source.organizations)),
+        "resources.name" => Text(|d| d.resources.iter().map(|r| r.name.as_str()).collect()),
+        "resources.url" => Text(|d| d.resources.iter().map(|r| r.url.as_str()).collect()),
+        "resources.format" => Text(|d| d.resources.iter().map(|r| r.format.as_str()).collect()),
+        "resources.mimetype" => Text(|d| d.resources.iter().map(|r| r.mimetype.as_str()).collect()),
+        _ => return None,
+    };
+    Some(field)
+}
+
+fn entity_ids(entities: &[SourceEntity]) -> Vec<&str> {
+    entities.iter().map(|e| e.id.as_str()).collect()
+}
+
+fn entity_names(entities: &[SourceEntity]) -> Vec<&str> {
+    entities.iter().map(|e| e.name.as_str()).collect()
+}
+
+/// Parse one filter expression, falling back to a literal `key=value`
+pub fn parse(input: &str) -> Result<Filter> {
+    let mut parser = Parser { input, pos: 0, depth: 0 };
+    parser.parse_filter().or_else(|e| parse_literal(input).ok_or(e))
+}
+
+/// `key=value` taken verbatim, as filters were written before expressions.
+/// Input using keywords or parentheses was meant as an expression, so its
+/// parse error stands rather than, say, `format=CSV AND formt=JSON` becoming
+/// a single `format` value.
+fn parse_literal(input: &str) -> Option<Filter> {
+    let keyword = |word: &str| matches!(word, "AND" | "OR" | "NOT" | "IN");
+    if input.contains(['(', ')']) || input.split_whitespace().any(keyword) {
+        return None;
+    }
+    let (key, value) = input.split_once('=')?;
+    let key = key.trim();
+    if !is_known_field(key) || value.trim().is_empty() {
+        return None;
+    }
+    Some(Filter::eq(key, value))
+}
+
+/// Read a `filters` parameter into filter expressions, ANDed together.
+///
+/// Accepts a `LIST(VARCHAR)` of expressions such as `format=CSV` or
+/// `updated_at >= 2023-01-01` (see [`parse`]), or a MAP or STRUCT from field
+/// path to a value or list of values, e.g. `MAP {'format': ['CSV', 'Parquet']}`,
+/// where a field matches any of its values. NULL entries are ignored.
+pub fn from_value(value: Option<Value>) -> Result<Vec<Filter>> {
+    let invalid = |message: &str| DatenoError::InvalidFilter(message.to_string());
+    let mut filters = Vec::new();
+    match value {
+        None | Some(Value::Null) => {}
+        Some(Value::List(items)) => {
+            for item in items {
+                match item {
+                    Value::Text(s) => filters.push(parse(&s)?),
+                    Value::Null => {}
+                    _ => return Err(invalid("filters list entries must be 'key=value' strings")),
+                }
+            }
+        }
+        Some(Value::Map(map)) => {
+            for (key, values) in map.iter() {
+                let Value::Text(key) = key else {
+                    return Err(invalid("filters MAP keys must be field paths such as 'format'"));
+                };
+                push_field_filter(&mut filters, key, values)?;
+            }
+        }
+        Some(Value::Struct(fields)) => {
+            for (key, values) in fields.iter() {
+                push_field_filter(&mut filters, key, values)?;
+            }
+        }
+        Some(_) => return Err(invalid("filters must be a list of 'key=value' strings, a MAP or a STRUCT")),
+    }
+    Ok(filters)
+}
+
+/// Append a `key IN (values)` filter for a MAP entry or STRUCT field
+fn push_field_filter(filters: &mut Vec<Filter>, key: &str, values: &Value) -> Result<()> {
+    if !is_known_field(key) {
+        return Err(DatenoError::InvalidFilter(format!(
+            "'{}' is not a known filter field such as 'format' or 'source.countries.name'",
+            key
+        )));
+    }
+    let values = match values {
+        Value::List(values) => values.as_slice(),
+        value => std::slice::from_ref(value),
+    };
+    let mut strings = Vec::with_capacity(values.len());
+    for value in values {
+        match value {
+            Value::Text(s) => strings.push(s.clone()),
+            Value::Null => {}
+            _ => {
+                return Err(DatenoError::InvalidFilter(format!(
+                    "values for filter field '{}' must be strings",
+                    key
+                )))
+            }
+        }
+    }
+    match strings.len() {
+        0 => {}
+        1 => filters.push(Filter::eq(key, &strings[0])),
+        _ => filters.push(Filter::In {
+            field: key.to_string(),
+            values: strings,
+        }),
+    }
+    Ok(())
+}
+
+/// Recursive-descent parser over the raw string; bare values need the
+/// surrounding text, so there is no separate tokenizer
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    /// Open parentheses, inside which a bare value also ends at `)`
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn parse_filter(&mut self) -> Result<Filter> {
+        let filter = self.parse_or()?;
+        self.skip_whitespace();
+        if self.pos < self.input.len() {
+            return Err(self.error("unexpected input"));
+        }
+        Ok(filter)
+    }
+
+    fn rest(&self) -> &str {
+        &self.input[self.pos..]
+    }
+
+    fn error(&self, message: &str) -> DatenoError {
+        DatenoError::InvalidFilter(format!(
+            "{} at position {} of '{}'. Use e.g. 'format=CSV', 'format IN (CSV, JSON)' or 'updated_at >= 2023-01-01'",
+            message, self.pos, self.input
+        ))
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(c) {
+            self.pos += c.len_utf8();
+            return true;
+        }
+        false
+    }
+
+    /// Whether an (uppercase) keyword starts at byte offset `at` of the remaining input
+    fn keyword_at(&self, at: usize, keyword: &str) -> bool {
+        let rest = &self.rest()[at..];
+        rest.starts_with(keyword) && !rest[keyword.len()..].starts_with(is_field_char)
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        self.skip_whitespace();
+        if self.keyword_at(0, keyword) {
+            self.pos += keyword.len();
+            return true;
+        }
+        false
+    }
+
+    fn parse_or(&mut self) -> Result<Filter> {
+        let mut terms = vec![self.parse_and()?];
+        while self.eat_keyword("OR") {
+            terms.push(self.parse_and()?);
+        }
+        Ok(if terms.len() == 1 { terms.remove(0) } else { Filter::Or(terms) })
+    }
+
+    fn parse_and(&mut self) -> Result<Filter> {
+        let mut terms = vec![self.parse_unary()?];
+        while self.eat_keyword("AND") {
+            terms.push(self.parse_unary()?);
+        }
+        Ok(if terms.len() == 1 { terms.remove(0) } else { Filter::And(terms) })
+    }
+
+    fn parse_unary(&mut self) -> Result<Filter> {
+        if self.eat_keyword("NOT") {
+            return Ok(Filter::Not(Box::new(self.parse_unary()?)));
+        }
+        if self.eat('(') {
+            self.depth += 1;
+            let filter = self.parse_or()?;
+            if !self.eat(')') {
+                return Err(self.error("expected ')'"));
+            }
+            self.depth -= 1;
+            return Ok(filter);
+        }
+        self.parse_predicate()
+    }
+
+    fn parse_predicate(&mut self) -> Result<Filter> {
+        self.skip_whitespace();
+        let len = self.rest().find(|c| !is_field_char(c)).unwrap_or(self.rest().len());
+        if len == 0 {
+            return Err(self.error("expected a field name"));
+        }
+        let field = self.rest()[..len].to_string();
+        if !is_valid_field_path(&field) {
+            return Err(self.error(&format!("'{}' is not a valid field path", field)));
+        }
+        if !is_known_field(&field) {
+            return Err(self.error(&format!("'{}' is not a known filter field", field)));
+        }
+        self.pos += len;
+
+        let negated = self.eat_keyword("NOT");
+        if self.eat_keyword("IN") {
+            let filter = Filter::In {
+                field,
+                values: self.parse_list()?,
+            };
+            return Ok(if negated { Filter::Not(Box::new(filter)) } else { filter });
+        }
+        if negated {
+            return Err(self.error("expected IN after NOT"));
+        }
+
+        self.skip_whitespace();
+        let Some((symbol, op)) = CompareOp::SYMBOLS.iter().find(|(symbol, _)| self.rest().starts_with(symbol)) else {
+            return Err(self.error("expected an operator (=, !=, <, <=, >, >=, IN)"));
+        };
+        self.pos += symbol.len();
+        let value = self.parse_value(false)?;
+        Ok(Filter::Compare { field, op: *op, value })
+    }
+
+    fn parse_list(&mut self) -> Result<Vec<String>> {
+        if !self.eat('(') {
+            return Err(self.error("expected '(' after IN"));
+        }
+        let mut values = vec![self.parse_value(true)?];
+        while self.eat(',') {
+            values.push(self.parse_value(true)?);
+        }
+        if !self.eat(')') {
+            return Err(self.error("expected ',' or ')'"));
+        }
+        Ok(values)
+    }
+
+    fn parse_value(&mut self, in_list: bool) -> Result<String> {
+        self.skip_whitespace();
+        match self.rest().chars().next() {
+            Some(quote @ ('\'' | '"')) => self.parse_quoted(quote),
+            _ => self.parse_bare(in_list),
+        }
+    }
+
+    /// A quoted value; the quote character is escaped by doubling it
+    fn parse_quoted(&mut self, quote: char) -> Result<String> {
+        self.pos += 1;
+        let input = self.input;
+        let mut value = String::new();
+        let mut chars = input[self.pos..].char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            if c != quote {
+                value.push(c);
+                continue;
+            }
+            if chars.peek().is_some_and(|(_, next)| *next == quote) {
+                chars.next();
+                value.push(quote);
+                continue;
+            }
+            self.pos += i + 1;
+            return Ok(value);
+        }
+        Err(self.error("unterminated quoted value"))
+    }
+
+    /// An unquoted value, ending at the next AND/OR keyword, at `)` inside
+    /// parentheses and at `,` inside an IN list
+    fn parse_bare(&mut self, in_list: bool) -> Result<String> {
+        let rest = self.rest();
+        let mut end = rest.len();
+        let mut previous = ' ';
+        for (i, c) in rest.char_indices() {
+            if (c == ')' && (in_list || self.depth > 0)) || (c == ',' && in_list) {
+                end = i;
+                break;
+            }
+            if previous.is_whitespace() && i > 0 && (self.keyword_at(i, "AND") || self.keyword_at(i, "OR")) {
+                end = i;
+                break;
+            }
+            previous = c;
+        }
+        let value = rest[..end].trim_end().to_string();
+        if value.is_empty() {
+            return Err(self.error("expected a value"));
+        }
+        self.pos += end;
+        Ok(value)
+    }
+}
+
+fn is_field_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '.'
+}
+
+/// Whether `path` is a dotted field path such as `source.countries.name`:
+/// identifiers of ASCII letters, digits and `_`, not starting with a digit
+fn is_valid_field_path(path: &str) -> bool {
+    !path.is_empty()
+        && path.split('.').all(|segment| {
+            segment.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                && segment.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_grammar() {
+        assert_eq!(parse("format=CSV").unwrap(), Filter::eq("format", "CSV"));
+        assert_eq!(
+            parse("source.countries.name=United States").unwrap(),
+            Filter::eq("source.countries.name", "United States")
+        );
+        assert_eq!(
+            parse("format IN (CSV, 'Excel, XLSX')").unwrap(),
+            Filter::In {
+                field: "format".to_string(),
+                values: strings(&["CSV", "Excel, XLSX"]),
+            }
+        );
+        assert_eq!(
+            parse("NOT source.countries.name = Russia").unwrap(),
+            Filter::Not(Box::new(Filter::eq("source.countries.name", "Russia")))
+        );
+        assert_eq!(
+            parse("updated_at >= 2023-01-01 AND (format=CSV OR title='O''Brien (2020)')").unwrap(),
+            Filter::And(vec![
+                Filter::Compare {
+                    field: "updated_at".to_string(),
+                    op: CompareOp::Ge,
+                    value: "2023-01-01".to_string(),
+                },
+                Filter::Or(vec![Filter::eq("format", "CSV"), Filter::eq("title", "O'Brien (2020)")]),
+            ])
+        );
+        assert!(matches!(parse("format NOT IN (CSV)").unwrap(), Filter::Not(_)));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for input in [
+            "",
+            "format",
+            "=CSV",
+            "format=",
+            "format IN CSV",
+            "(format=CSV",
+            "format in (CSV)",
+            "source..name=x",
+            "formt=CSV",
+            "formt IN (CSV, JSON)",
+            "format=CSV AND formt=JSON",
+            "title=Research AND Development",
+        ] {
+            assert!(
+                matches!(parse(input), Err(DatenoError::InvalidFilter(_))),
+                "{:?} should not parse",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_keeps_legacy_filters() {
+        // Lowercase words are part of the value, not keywords
+        for (input, field, value) in [
+            (
+                "source.organizations.name=Department of Fisheries and Oceans",
+                "source.organizations.name",
+                "Department of Fisheries and Oceans",
+            ),
+            ("title=Research and Development", "title", "Research and Development"),
+            ("title=Pride or prejudice", "title", "Pride or prejudice"),
+        ] {
+            assert_eq!(parse(input).unwrap(), Filter::eq(field, value), "{}", input);
+        }
+
+        // Strings that are not valid expressions are taken verbatim, unless
+        // they use keywords; those values need quoting
+        for (input, field, value) in [
+            ("title=\"Foo\" bar", "title", "\"Foo\" bar"),
+            ("title='Research AND Development'", "title", "Research AND Development"),
+            ("title='x", "title", "'x"),
+        ] {
+            assert_eq!(parse(input).unwrap(), Filter::eq(field, value), "{}", input);
+        }
+
+        // Expressions still take precedence
+        assert_eq!(
+            parse("title=Research AND format=CSV").unwrap(),
+            Filter::And(vec![Filter::eq("title", "Research"), Filter::eq("format", "CSV")])
+        );
+    }
+
+    #[test]
+    fn test_from_value() {
+        use duckdb::types::OrderedMap;
+
+        let text = |s: &str| Value::Text(s.to_string());
+        let list = |values: &[&str]| Value::List(values.iter().map(|v| text(v)).collect());
+
+        let map = Value::Map(OrderedMap::from(vec![
+            (text("format"), list(&["CSV", "Parquet"])),
+            (text("source.countries.name"), list(&["Canada"])),
+        ]));
+        assert_eq!(
+            from_value(Some(map)).unwrap(),
+            vec![
+                Filter::In {
+                    field: "format".to_string(),
+                    values: strings(&["CSV", "Parquet"]),
+                },
+                Filter::eq("source.countries.name", "Canada"),
+            ]
+        );
+
+        let fields = Value::Struct(OrderedMap::from(vec![
+            ("format".to_string(), text("CSV")),
+            ("source.langs.id".to_string(), text("FR")),
+        ]));
+        assert_eq!(
+            from_value(Some(fields)).unwrap(),
+            vec![Filter::eq("format", "CSV"), Filter::eq("source.langs.id", "FR")]
+        );
+
+        // NULLs are ignored, whether the whole parameter, an entry or a value
+        assert!(from_value(None).unwrap().is_empty());
+        assert!(from_value(Some(Value::Null)).unwrap().is_empty());
+        let nulls = Value::Map(OrderedMap::from(vec![
+            (text("format"), Value::Null),
+            (text("title"), Value::List(vec![Value::Null, text("Air")])),
+        ]));
+        assert_eq!(from_value(Some(nulls)).unwrap(), vec![Filter::eq("title", "Air")]);
+        let entries = Value::List(vec![text("format=CSV"), Value::Null]);
+        assert_eq!(from_value(Some(entries)).unwrap(), vec![Filter::eq("format", "CSV")]);
+
+        let rejected = [
+            Value::BigInt(1),
+            Value::List(vec![Value::BigInt(1)]),
+            Value::Map(OrderedMap::from(vec![(text("format"), Value::BigInt(1))])),
+            Value::Map(OrderedMap::from(vec![(Value::BigInt(1), text("CSV"))])),
+            Value::Struct(OrderedMap::from(vec![("format".to_string(), Value::Boolean(true))])),
+            Value::Map(OrderedMap::from(vec![(text("format'; --"), text("CSV"))])),
+            Value::Struct(OrderedMap::from(vec![("".to_string(), text("CSV"))])),
+        ];
+        for value in rejected {
+            assert!(
+                matches!(from_value(Some(value.clone())), Err(DatenoError::InvalidFilter(_))),
+                "{:?} should be rejected",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_compile_splits_api_and_local() {
+        let filters: Vec<Filter> = [
+            "format IN (CSV, JSON)",
+            "source.countries.name=Canada OR source.countries.name=Mexico",
+            "format=CSV AND source.langs.id IN (FR)",
+            "source.countries.name=Canada",
+            "updated_at >= 2023-01-01",
+            "NOT source.langs.id=FR",
+        ]
+        .iter()
+        .map(|f| parse(f).unwrap())
+        .collect();
+        let compiled = compile(&filters).unwrap();
+
+        // The API ANDs repeated filters, so only single equalities are sent
+        assert_eq!(
+            compiled.api,
+            strings(&["format=CSV", "source.langs.id=FR", "source.countries.name=Canada"])
+        );
+        assert_eq!(compiled.local.len(), 4);
+        assert_eq!(compiled.local[0], parse("format IN (CSV, JSON)").unwrap());
+        assert!(matches!(compiled.local[1], Filter::Or(_)));
+
+        // Aliases are sent under the API's key, and fields the API cannot
+        // filter on are checked locally
+        let filters: Vec<Filter> = [
+            "source.languages.id=FR",
+            "source.catalog_name=NOAA",
+            "title=Air",
+            "resources.format=CSV",
+        ]
+        .iter()
+        .map(|f| parse(f).unwrap())
+        .collect();
+        let compiled = compile(&filters).unwrap();
+        assert_eq!(compiled.api, strings(&["source.langs.id=FR", "source.name=NOAA"]));
+        assert_eq!(compiled.local, vec![Filter::eq("title", "Air"), Filter::eq("resources.format", "CSV")]);
+
+        let unknown = Filter::Compare {
+            field: "source.owner_type".to_string(),
+            op: CompareOp::Ne,
+            value: "Government".to_string(),
+        };
+        assert!(compile(&[unknown]).is_err());
+        assert!(compile(&[parse("updated_at > soon").unwrap()]).is_err());
+    }
+
+    #[test]
+    fn test_matches_dataset() {
+        let dataset: Dataset = serde_json::from_value(serde_json::json!({
+            "id": "1",
+            "title": "Air quality",
+            "format": "CSV",
+            "updated_at": "2023-06-01T00:00:00Z",
+            "source": {"countries": [{"id": "CA", "name": "Canada"}, {"id": "US", "name": "United States"}]}
+        }))
+        .unwrap();
+
+        let check = |input: &str| parse(input).unwrap().matches(&dataset);
+        assert!(check("updated_at >= 2023-01-01 AND updated_at < 2024"));
+        assert!(!check("created_at < 2024"));
+        assert!(check("source.countries.name = 'United States'"));
+        assert!(!check("NOT source.countries.id IN (US, MX)"));
+        assert!(check("source.countries.name != Russia"));
+        assert!(check("format=JSON OR title='Air quality'"));
+    }
+}
@@ -0,0 +1,117 @@
+use crate::api::Dataset;
+use crate::table_function::{
+    emit_once, init_bind_data, list_offsets, positional_parameter, write_row, ClientConfig, PageInfo, Column,
+    DatenoTableFunction,
+};
+use duckdb::{
+    core::{DataChunkHandle, LogicalTypeHandle, LogicalTypeId},
+    types::Value,
+    vtab::{BindInfo, InitInfo, TableFunctionInfo, VTab},
+};
+use std::error::Error;
+use std::sync::Mutex;
+
+/// Bind data for `dateno_get` (read-only, shared across threads)
+#[derive(Clone)]
+pub struct DatenoGetBindData {
+    pub client_config: ClientConfig,
+    pub id: String,
+}
+
+/// Init data for `dateno_get`; the record is fetched once and emitted by the first `func` call
+pub struct DatenoGetInitData {
+    /// Columns DuckDB asked for, in output chunk order
+    pub columns: Vec<Column>,
+    pub entry: Mutex<Option<Dataset>>,
+}
+
+/// `dateno_get(id)`: fetch the full record of a single dataset
+pub struct DatenoGetFunction;
+
+/// Search columns followed by the full record as JSON
+fn get_columns() -> Vec<Column> {
+    let mut columns = Column::ALL.to_vec();
+    columns.push(Column::Raw);
+    columns
+}
+
+impl VTab for DatenoGetFunction {
+    type InitData = DatenoGetInitData;
+    type BindData = DatenoGetBindData;
+
+    // Only the columns a query references are passed to init
+    fn supports_pushdown() -> bool {
+        true
+    }
+
+    fn parameters() -> Option<Vec<LogicalTypeHandle>> {
+        Some(vec![
+            LogicalTypeId::Varchar.into(), // id (required)
+        ])
+    }
+
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        let id = match positional_parameter(bind, 0) {
+            Some(Value::Text(s)) if !s.trim().is_empty() => s,
+            None => {
+                bind.set_error("id parameter is required");
+                return Err("id parameter is required".into());
+            }
+            _ => {
+                bind.set_error("id parameter must be a non-empty string");
+                return Err("id parameter must be a non-empty string".into());
+            }
+        };
+
+        let bind_data = DatenoGetBindData {
+            client_config: ClientConfig::resolve()?,
+            id,
+        };
+        Self::add_result_columns(bind, &bind_data);
+        Ok(bind_data)
+    }
+
+    fn init(init: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        let bind_data = init_bind_data::<DatenoGetBindData>(init)?;
+
+        let schema = get_columns();
+        let columns = init
+            .get_column_indices()
+            .into_iter()
+            .filter_map(|index| schema.get(index as usize).copied())
+            .collect();
+
+        let client = bind_data.client_config.client()?;
+        let entry = client
+            .get_entry(&bind_data.id)
+            .map_err(|e| format!("Failed to fetch dataset '{}': {}", bind_data.id, e))?;
+
+        Ok(DatenoGetInitData {
+            columns,
+            entry: Mutex::new(Some(entry)),
+        })
+    }
+
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init_data = func.get_init_data();
+
+        emit_once(&init_data.entry, output, |dataset, output| {
+            let mut list_offsets = list_offsets(init_data.columns.len());
+            write_row(output, 0, &dataset, &PageInfo::default(), &init_data.columns, &mut list_offsets);
+        })
+    }
+}
+
+impl DatenoTableFunction for DatenoGetFunction {
+    const NAME: &'static str = "dateno_get";
+
+    fn result_columns(_bind_data: &Self::BindData) -> Vec<(&'static str, LogicalTypeHandle)> {
+        get_columns()
+            .into_iter()
+            .map(|column| (column.name(), column.logical_type()))
+            .collect()
+    }
+}
@@ -0,0 +1,147 @@
+use crate::api::{Dataset, SearchPager};
+use crate::table_function::{
+    init_bind_data, write_timestamp, DatenoSearchBindData, DatenoSearchFunction, DatenoTableFunction,
+    STANDARD_VECTOR_SIZE,
+};
+use duckdb::{
+    core::{DataChunkHandle, Inserter, LogicalTypeHandle, LogicalTypeId},
+    vtab::{BindInfo, InitInfo, TableFunctionInfo, VTab},
+};
+use std::error::Error;
+use std::sync::Mutex;
+
+/// Init data for `dateno_resources`
+pub struct DatenoResourcesInitData {
+    pub state: Mutex<DatenoResourcesScanState>,
+}
+
+/// Scan progress: the current page of datasets and the next resource to emit
+pub struct DatenoResourcesScanState {
+    pub pager: SearchPager,
+    pub results: Vec<Dataset>,
+    pub dataset_index: usize,
+    pub resource_index: usize,
+}
+
+/// `dateno_resources(query, filters, limit)`: one row per downloadable file of
+/// the matching datasets. Takes the same parameters as `dateno_search`; `limit`
+/// counts datasets, not resources.
+pub struct DatenoResourcesFunction;
+
+impl VTab for DatenoResourcesFunction {
+    type InitData = DatenoResourcesInitData;
+    type BindData = DatenoSearchBindData;
+
+    fn parameters() -> Option<Vec<LogicalTypeHandle>> {
+        DatenoSearchFunction::parameters()
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalTypeHandle)>> {
+        DatenoSearchFunction::named_parameters()
+    }
+
+    fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
+        let bind_data = DatenoSearchBindData::from_bind(bind)?;
+        Self::add_result_columns(bind, &bind_data);
+        Ok(bind_data)
+    }
+
+    fn init(init: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        let bind_data = init_bind_data::<DatenoSearchBindData>(init)?;
+
+        let client = bind_data.client_config.client()?;
+        let pager = bind_data.pager(client, vec!["id", "title", "resources"])?;
+
+        Ok(DatenoResourcesInitData {
+            state: Mutex::new(DatenoResourcesScanState {
+                pager,
+                results: Vec::new(),
+                dataset_index: 0,
+                resource_index: 0,
+            }),
+        })
+    }
+
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init_data = func.get_init_data();
+
+        let mut state = init_data
+            .state
+            .lock()
+            .map_err(|_| "Scan state lock poisoned")?;
+
+        let metadata = func.get_bind_data().metadata;
+        let mut row = 0;
+        while row < STANDARD_VECTOR_SIZE {
+            if state.dataset_index >= state.results.len() {
+                match state
+                    .pager
+                    .next_page()
+                    .map_err(|e| format!("Search failed: {}", e))?
+                {
+                    Some(page) => {
+                        state.results = page;
+                        state.dataset_index = 0;
+                        state.resource_index = 0;
+                    }
+                    None => break,
+                }
+                continue;
+            }
+
+            let dataset = &state.results[state.dataset_index];
+            let Some(resource) = dataset.resources.get(state.resource_index) else {
+                state.dataset_index += 1;
+                state.resource_index = 0;
+                continue;
+            };
+
+            output.flat_vector(0).insert(row, dataset.id.as_str());
+            output.flat_vector(1).insert(row, dataset.title.as_str());
+            output.flat_vector(2).insert(row, resource.name.as_str());
+            output.flat_vector(3).insert(row, resource.url.as_str());
+            output.flat_vector(4).insert(row, resource.format.as_str());
+            output.flat_vector(5).insert(row, resource.mimetype.as_str());
+            match resource.size {
+                Some(bytes) => output.flat_vector(6).as_mut_slice::<i64>()[row] = bytes as i64,
+                None => output.flat_vector(6).set_null(row),
+            }
+            write_timestamp(&mut output.flat_vector(7), row, resource.last_modified);
+            if metadata {
+                output.flat_vector(8).as_mut_slice::<i64>()[row] = state.pager.skipped() as i64;
+            }
+
+            state.resource_index += 1;
+            row += 1;
+        }
+
+        // An empty chunk tells DuckDB the scan is complete
+        output.set_len(row);
+
+        Ok(())
+    }
+}
+
+impl DatenoTableFunction for DatenoResourcesFunction {
+    const NAME: &'static str = "dateno_resources";
+
+    fn result_columns(bind_data: &Self::BindData) -> Vec<(&'static str, LogicalTypeHandle)> {
+        let mut columns = vec![
+            ("dataset_id", LogicalTypeId::Varchar.into()),
+            ("dataset_title", LogicalTypeId::Varchar.into()),
+            ("name", LogicalTypeId::Varchar.into()),
+            ("url", LogicalTypeId::Varchar.into()),
+            ("format", LogicalTypeId::Varchar.into()),
+            ("mimetype", LogicalTypeId::Varchar.into()),
+            ("size", LogicalTypeId::Bigint.into()),
+            ("last_modified", LogicalTypeId::Timestamp.into()),
+        ];
+        if bind_data.metadata {
+            columns.push(("_skipped", LogicalTypeId::Bigint.into()));
+        }
+        columns
+    }
+}
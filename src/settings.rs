@@ -0,0 +1,181 @@
+use crate::api::{RetryPolicy, DEFAULT_BASE_URL, DEFAULT_MAX_SCAN_PAGES};
+use crate::cache::{CachePolicy, DiskCache, DiskCacheMode};
+use std::str::FromStr;
+use std::time::Duration;
+
+// Configuration is read from environment variables: DuckDB's C extension API,
+// and so duckdb-rs, can neither register custom options (`SET dateno_...`)
+// nor read settings from inside a table function.
+
+/// Dateno API key sent with every request
+pub const API_KEY_ENV: &str = "DATENO_API_KEY";
+
+/// Overrides the Dateno API base URL
+pub const BASE_URL_ENV: &str = "DATENO_BASE_URL";
+
+/// Total number of attempts per API request
+pub const RETRY_MAX_ATTEMPTS_ENV: &str = "DATENO_RETRY_MAX_ATTEMPTS";
+
+/// Delay before the first retry, in milliseconds
+pub const RETRY_BASE_DELAY_ENV: &str = "DATENO_RETRY_BASE_DELAY_MS";
+
+/// Toggles randomised retry delays
+pub const RETRY_JITTER_ENV: &str = "DATENO_RETRY_JITTER";
+
+/// How long cached API responses stay valid, in seconds
+pub const CACHE_TTL_ENV: &str = "DATENO_CACHE_TTL_SECONDS";
+
+/// Most API responses kept in the in-memory cache
+pub const CACHE_MAX_ENTRIES_ENV: &str = "DATENO_CACHE_MAX_ENTRIES";
+
+/// Most pages a search fetches while checking filters the API cannot apply
+pub const MAX_SCAN_PAGES_ENV: &str = "DATENO_MAX_SCAN_PAGES";
+
+/// Directory of the persistent response cache
+pub const CACHE_DIR_ENV: &str = "DATENO_CACHE_DIR";
+
+/// How the persistent response cache is used
+pub const CACHE_MODE_ENV: &str = "DATENO_CACHE_MODE";
+
+/// Read an environment variable, treating unset and blank values as absent
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|s| !s.trim().is_empty())
+}
+
+/// Parse the value of `name`, if set, naming the variable in the error
+fn parse_var<T: FromStr>(
+    lookup: &impl Fn(&str) -> Option<String>,
+    name: &str,
+    expected: &str,
+) -> Result<Option<T>, String> {
+    match lookup(name) {
+        Some(value) => value
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|_| format!("Invalid {} '{}'; expected {}", name, value, expected)),
+        None => Ok(None),
+    }
+}
+
+/// Resolve the API base URL: `DATENO_BASE_URL`, then the public endpoint
+pub fn resolve_base_url() -> String {
+    env_var(BASE_URL_ENV).unwrap_or_else(|| DEFAULT_BASE_URL.to_string())
+}
+
+/// Resolve the API key from `DATENO_API_KEY`
+pub fn resolve_api_key() -> Option<String> {
+    env_var(API_KEY_ENV)
+}
+
+/// Resolve the retry policy from the `DATENO_RETRY_*` variables
+pub fn resolve_retry_policy() -> Result<RetryPolicy, String> {
+    retry_policy_from(&env_var)
+}
+
+fn retry_policy_from(lookup: &impl Fn(&str) -> Option<String>) -> Result<RetryPolicy, String> {
+    let mut policy = RetryPolicy::default();
+    if let Some(n) = parse_var::<u32>(lookup, RETRY_MAX_ATTEMPTS_ENV, "a positive integer")? {
+        policy.max_attempts = n.max(1);
+    }
+    if let Some(ms) = parse_var::<u64>(lookup, RETRY_BASE_DELAY_ENV, "a number of milliseconds")? {
+        policy.base_delay = Duration::from_millis(ms);
+    }
+    if let Some(jitter) = parse_var::<bool>(lookup, RETRY_JITTER_ENV, "true or false")? {
+        policy.jitter = jitter;
+    }
+    Ok(policy)
+}
+
+/// Resolve the page cap for locally filtered searches from `DATENO_MAX_SCAN_PAGES`
+pub fn resolve_max_scan_pages() -> Result<u32, String> {
+    max_scan_pages_from(&env_var)
+}
+
+fn max_scan_pages_from(lookup: &impl Fn(&str) -> Option<String>) -> Result<u32, String> {
+    let pages = parse_var::<u32>(lookup, MAX_SCAN_PAGES_ENV, "a positive integer")?;
+    Ok(pages.map_or(DEFAULT_MAX_SCAN_PAGES, |n| n.max(1)))
+}
+
+/// Resolve the response cache policy from the `DATENO_CACHE_*` variables
+pub fn resolve_cache_policy() -> Result<CachePolicy, String> {
+    cache_policy_from(&env_var)
+}
+
+fn cache_policy_from(lookup: &impl Fn(&str) -> Option<String>) -> Result<CachePolicy, String> {
+    let mut policy = CachePolicy::default();
+    if let Some(secs) = parse_var::<u64>(lookup, CACHE_TTL_ENV, "a number of seconds")? {
+        policy.ttl = Duration::from_secs(secs);
+    }
+    if let Some(n) = parse_var::<usize>(lookup, CACHE_MAX_ENTRIES_ENV, "a non-negative integer")? {
+        policy.max_entries = n;
+    }
+    if let Some(dir) = lookup(CACHE_DIR_ENV) {
+        let mode = match lookup(CACHE_MODE_ENV) {
+            Some(name) => DiskCacheMode::parse(&name).ok_or_else(|| {
+                format!(
+                    "Invalid {} '{}'; expected one of: {}",
+                    CACHE_MODE_ENV,
+                    name,
+                    DiskCacheMode::NAMES.join(", ")
+                )
+            })?,
+            None => DiskCacheMode::default(),
+        };
+        policy.disk = Some(DiskCache {
+            dir: dir.into(),
+            mode,
+        });
+    }
+    Ok(policy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+        move |name| pairs.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string())
+    }
+
+    #[test]
+    fn test_retry_policy_from_env() {
+        let policy = retry_policy_from(&vars(&[
+            (RETRY_MAX_ATTEMPTS_ENV, "5"),
+            (RETRY_BASE_DELAY_ENV, " 250 "),
+            (RETRY_JITTER_ENV, "false"),
+        ]))
+        .unwrap();
+        assert_eq!(policy.max_attempts, 5);
+        assert_eq!(policy.base_delay, Duration::from_millis(250));
+        assert!(!policy.jitter);
+
+        assert_eq!(retry_policy_from(&vars(&[])).unwrap(), RetryPolicy::default());
+        assert!(retry_policy_from(&vars(&[(RETRY_MAX_ATTEMPTS_ENV, "many")])).is_err());
+    }
+
+    #[test]
+    fn test_max_scan_pages_from_env() {
+        assert_eq!(max_scan_pages_from(&vars(&[])).unwrap(), DEFAULT_MAX_SCAN_PAGES);
+        assert_eq!(max_scan_pages_from(&vars(&[(MAX_SCAN_PAGES_ENV, "200")])).unwrap(), 200);
+        assert_eq!(max_scan_pages_from(&vars(&[(MAX_SCAN_PAGES_ENV, "0")])).unwrap(), 1);
+        assert!(max_scan_pages_from(&vars(&[(MAX_SCAN_PAGES_ENV, "-1")])).is_err());
+    }
+
+    #[test]
+    fn test_cache_policy_from_env() {
+        let policy = cache_policy_from(&vars(&[
+            (CACHE_TTL_ENV, "0"),
+            (CACHE_DIR_ENV, "snapshot"),
+            (CACHE_MODE_ENV, "read_only"),
+        ]))
+        .unwrap();
+        assert!(!policy.memory_enabled());
+        let disk = policy.disk.unwrap();
+        assert_eq!(disk.dir, std::path::PathBuf::from("snapshot"));
+        assert_eq!(disk.mode, DiskCacheMode::ReadOnly);
+
+        let err = cache_policy_from(&vars(&[(CACHE_DIR_ENV, "snapshot"), (CACHE_MODE_ENV, "sometimes")]));
+        assert!(err.unwrap_err().contains(CACHE_MODE_ENV));
+    }
+}
@@ -1,23 +1,365 @@
-use crate::api::{DatenoClient, Dataset, SearchRequest};
+use crate::api::{
+    DatenoClient, Dataset, Resource, RetryPolicy, SearchPager, SearchRequest, SortOrder, SourceEntity,
+};
+use crate::cache::CachePolicy;
 use crate::error::DatenoError;
+use crate::filters::{self, Filter};
+use crate::settings;
+use chrono::{DateTime, Utc};
 use duckdb::{
-    types::{LogicalType, Value},
-    vtab::{BindInfo, DataChunkHandle, InitInfo, TableFunctionInfo, VTab},
+    core::{DataChunkHandle, FlatVector, Inserter, ListVector, LogicalTypeHandle, LogicalTypeId, StructVector},
+    types::Value,
+    vtab::{BindInfo, InitInfo, TableFunctionInfo, VTab},
 };
 use std::error::Error;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+
+/// Maximum number of rows DuckDB accepts in a single output chunk
+pub(crate) const STANDARD_VECTOR_SIZE: usize = 2048;
+
+/// Most list vectors a single column holds (`source` has countries, languages and organizations)
+const MAX_COLUMN_LISTS: usize = 3;
+
+/// Entries already written to each list child vector of the current chunk,
+/// per output column and per list within that column
+pub(crate) type ListOffsets = Vec<[usize; MAX_COLUMN_LISTS]>;
+
+/// Fresh list offsets for a chunk with `columns` output columns
+pub(crate) fn list_offsets(columns: usize) -> ListOffsets {
+    vec![[0; MAX_COLUMN_LISTS]; columns]
+}
+
+/// Columns returned by `dateno_search`, in schema order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    Id,
+    Title,
+    Description,
+    Format,
+    Source,
+    Url,
+    CreatedAt,
+    UpdatedAt,
+    Formats,
+    Resources,
+    /// Full API record as JSON; returned by `dateno_get` and by `dateno_search` with `raw := true`
+    Raw,
+    /// Total number of hits for the query (metadata)
+    TotalHits,
+    /// Relevance score of the hit (metadata)
+    Score,
+    /// Problems found while decoding the record (metadata)
+    Warnings,
+    /// Cursor for the page after the row's page (metadata)
+    NextCursor,
+    /// Malformed results dropped up to the row's page (metadata)
+    Skipped,
+}
+
+impl Column {
+    /// Schema of `dateno_search`, in column index order
+    pub const ALL: [Column; 10] = [
+        Column::Id,
+        Column::Title,
+        Column::Description,
+        Column::Format,
+        Column::Source,
+        Column::Url,
+        Column::CreatedAt,
+        Column::UpdatedAt,
+        Column::Formats,
+        Column::Resources,
+    ];
+
+    /// Metadata columns appended to `dateno_search` with `metadata := true`
+    pub const METADATA: [Column; 5] = [
+        Column::TotalHits,
+        Column::Score,
+        Column::Warnings,
+        Column::NextCursor,
+        Column::Skipped,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Column::Id => "id",
+            Column::Title => "title",
+            Column::Description => "description",
+            Column::Format => "format",
+            Column::Source => "source",
+            Column::Url => "url",
+            Column::CreatedAt => "created_at",
+            Column::UpdatedAt => "updated_at",
+            Column::Formats => "formats",
+            Column::Resources => "resources",
+            Column::Raw => "raw",
+            Column::TotalHits => "_total_hits",
+            Column::Score => "_score",
+            Column::Warnings => "_warnings",
+            Column::NextCursor => "_next_cursor",
+            Column::Skipped => "_skipped",
+        }
+    }
+
+    pub fn logical_type(self) -> LogicalTypeHandle {
+        match self {
+            Column::CreatedAt | Column::UpdatedAt => LogicalTypeId::Timestamp.into(),
+            Column::Formats | Column::Warnings => LogicalTypeHandle::list(&LogicalTypeId::Varchar.into()),
+            Column::Source => source_type(),
+            Column::Resources => LogicalTypeHandle::list(&resource_type()),
+            Column::Raw => {
+                // DuckDB's JSON type is VARCHAR under the JSON alias
+                let json = LogicalTypeHandle::from(LogicalTypeId::Varchar);
+                json.set_alias("JSON");
+                json
+            }
+            Column::TotalHits | Column::Skipped => LogicalTypeId::Bigint.into(),
+            Column::Score => LogicalTypeId::Double.into(),
+            _ => LogicalTypeId::Varchar.into(),
+        }
+    }
+
+    /// Field name to request from the API, if the column maps to one
+    pub fn api_field(self) -> Option<&'static str> {
+        match self {
+            Column::Raw
+            | Column::TotalHits
+            | Column::Score
+            | Column::Warnings
+            | Column::NextCursor
+            | Column::Skipped => None,
+            _ => Some(self.name()),
+        }
+    }
+}
+
+/// Common interface of the extension's table functions, registered by `lib.rs`
+pub trait DatenoTableFunction: VTab {
+    /// SQL name the function is registered under
+    const NAME: &'static str;
+
+    /// Result columns for a bound call, in output order
+    fn result_columns(bind_data: &Self::BindData) -> Vec<(&'static str, LogicalTypeHandle)>;
+
+    /// Declare the result columns of a bound call; every `bind` ends with this
+    fn add_result_columns(bind: &BindInfo, bind_data: &Self::BindData) {
+        for (name, logical_type) in Self::result_columns(bind_data) {
+            bind.add_result_column(name, logical_type);
+        }
+    }
+}
+
+/// Client settings resolved at bind time, shared by every table function
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    pub base_url: String,
+    /// API key from `DATENO_API_KEY`
+    pub api_key: Option<String>,
+    pub retry: RetryPolicy,
+    pub cache: CachePolicy,
+    /// Page cap for searches with local filters, from `DATENO_MAX_SCAN_PAGES`
+    pub max_scan_pages: u32,
+}
+
+impl ClientConfig {
+    /// Resolve the base URL, API key, retry and cache policies and the scan cap
+    pub fn resolve() -> Result<Self, Box<dyn Error>> {
+        Ok(Self {
+            base_url: settings::resolve_base_url(),
+            api_key: settings::resolve_api_key(),
+            retry: settings::resolve_retry_policy()?,
+            cache: settings::resolve_cache_policy()?,
+            max_scan_pages: settings::resolve_max_scan_pages()?,
+        })
+    }
+
+    /// Get the pooled client for this configuration
+    pub fn client(&self) -> Result<Arc<DatenoClient>, Box<dyn Error>> {
+        DatenoClient::shared(&self.base_url, self.api_key.clone(), self.retry.clone(), self.cache.clone())
+            .map_err(|e| format!("Failed to create client: {}", e).into())
+    }
+}
 
 /// Bind data for the table function (read-only, shared across threads)
 #[derive(Clone)]
 pub struct DatenoSearchBindData {
+    pub client_config: ClientConfig,
     pub query: String,
-    pub filters: Vec<String>,
-    pub limit: i64,
+    /// Filter expressions from `filters` and the shorthand parameters, ANDed
+    pub filters: Vec<Filter>,
+    /// Maximum number of rows to return; `None` fetches every page
+    pub limit: Option<i64>,
+    pub offset: i64,
+    pub sort: Option<String>,
+    /// Opaque token continuing an earlier search, from its `_next_cursor` column
+    pub cursor: Option<String>,
+    /// Whether to add the `raw` JSON column with each full original record
+    pub raw: bool,
+    /// Whether to add the `_total_hits`, `_score`, ... metadata columns
+    /// (`dateno_resources` adds only `_skipped`)
+    pub metadata: bool,
+    /// Fail on undecodable results; when `false` they are dropped and counted
+    pub strict: bool,
+}
+
+impl DatenoSearchBindData {
+    /// Read the search parameters shared by `dateno_search` and `dateno_resources`
+    pub fn from_bind(bind: &BindInfo) -> Result<Self, Box<dyn Error>> {
+        // Get query parameter (required, first parameter)
+        let query = query_parameter(bind)?;
+
+        // Get filters parameter (optional, second parameter or `filters :=`)
+        let mut filters = match filters::from_value(get_parameter(bind, 1, "filters")) {
+            Ok(filters) => filters,
+            Err(e) => {
+                bind.set_error(&e.to_string());
+                return Err(e.into());
+            }
+        };
+
+        // Shorthand filters for the most common facets
+        if let Some(Value::Text(country)) = bind.get_named_parameter("country") {
+            filters.push(Filter::eq("source.countries.name", &country));
+        }
+        if let Some(Value::Text(format)) = bind.get_named_parameter("format") {
+            filters.push(Filter::eq("format", &format));
+        }
+
+        // Reject filters that can neither be sent to the API nor checked locally
+        if let Err(e) = filters::compile(&filters) {
+            bind.set_error(&e.to_string());
+            return Err(e.into());
+        }
+
+        // Get limit parameter (optional, third parameter or `limit :=`); NULL means no limit
+        let mut limit = Some(10i64); // default
+        match get_parameter(bind, 2, "limit") {
+            Some(Value::BigInt(n)) => limit = Some(n),
+            Some(Value::Null) => limit = None,
+            _ => {}
+        }
+
+        // Validate limit
+        if limit.is_some_and(|n| n < 1) {
+            bind.set_error("limit must be a positive integer or NULL");
+            return Err("limit must be a positive integer or NULL".into());
+        }
+
+        // Get offset parameter (optional, `offset :=` only)
+        let mut offset = 0i64;
+        if let Some(Value::BigInt(n)) = bind.get_named_parameter("offset") {
+            offset = n;
+        }
+        if offset < 0 {
+            bind.set_error("offset must not be negative");
+            return Err("offset must not be negative".into());
+        }
+
+        // Get sort parameter (optional, `sort :=` only), e.g. 'updated_at desc'
+        let sort = match bind.get_named_parameter("sort") {
+            Some(Value::Text(s)) if !s.trim().is_empty() => match SortOrder::parse(&s) {
+                Ok(order) => order.to_param(),
+                Err(e) => {
+                    bind.set_error(&e.to_string());
+                    return Err(e.into());
+                }
+            },
+            _ => None,
+        };
+
+        // Get cursor parameter (optional, `cursor :=` only); it replaces offset paging
+        let cursor = match bind.get_named_parameter("cursor") {
+            Some(Value::Text(s)) if !s.trim().is_empty() => Some(s),
+            _ => None,
+        };
+        if cursor.is_some() && offset > 0 {
+            bind.set_error("offset and cursor cannot be combined");
+            return Err("offset and cursor cannot be combined".into());
+        }
+
+        // Get raw parameter (optional, `raw :=` only)
+        let raw = matches!(bind.get_named_parameter("raw"), Some(Value::Boolean(true)));
+
+        // Get metadata parameter (optional, `metadata :=` only)
+        let metadata = matches!(bind.get_named_parameter("metadata"), Some(Value::Boolean(true)));
+
+        // Get strict parameter (optional, `strict :=` only); defaults to true
+        let strict = !matches!(bind.get_named_parameter("strict"), Some(Value::Boolean(false)));
+
+        Ok(Self {
+            client_config: ClientConfig::resolve()?,
+            query,
+            filters,
+            limit,
+            offset,
+            sort,
+            cursor,
+            raw,
+            metadata,
+            strict,
+        })
+    }
+
+    /// Result columns of this call, in schema order
+    pub fn columns(&self) -> Vec<Column> {
+        let mut columns = Column::ALL.to_vec();
+        if self.raw {
+            columns.push(Column::Raw);
+        }
+        if self.metadata {
+            columns.extend(Column::METADATA);
+        }
+        columns
+    }
+
+    /// Build the pager for this search, requesting only `fields` (empty for full records)
+    pub fn pager(&self, client: Arc<DatenoClient>, fields: Vec<&str>) -> Result<SearchPager, DatenoError> {
+        // Whatever the API cannot express is checked by the pager instead
+        let compiled = filters::compile(&self.filters)?;
+
+        // Local filters need their fields in every result
+        let mut fields: Vec<String> = fields.into_iter().map(String::from).collect();
+        if !fields.is_empty() {
+            for filter in &compiled.local {
+                for path in filter.fields() {
+                    let root = filters::root_field(path);
+                    if !fields.iter().any(|f| f == root) {
+                        fields.push(root.to_string());
+                    }
+                }
+            }
+        }
+
+        let limit = self.limit.map(|n| n as u64);
+
+        let request = SearchRequest {
+            query: self.query.clone(),
+            filters: compiled.api,
+            offset: self.offset,
+            sort: self.sort.clone(),
+            cursor: self.cursor.clone(),
+            fields,
+            keep_raw: self.raw,
+            skip_malformed: !self.strict,
+            ..Default::default()
+        };
+        Ok(SearchPager::new(client, request, limit)
+            .with_local_filters(compiled.local)
+            .with_max_scan_pages(self.client_config.max_scan_pages))
+    }
 }
 
 /// Init data for the table function (mutable state)
 pub struct DatenoSearchInitData {
     pub client: Arc<DatenoClient>,
+    /// Columns DuckDB asked for, in output chunk order
+    pub columns: Vec<Column>,
+    pub state: Mutex<DatenoSearchScanState>,
+}
+
+/// Scan progress, guarded by a mutex because `func` only receives shared init data
+pub struct DatenoSearchScanState {
+    pub pager: SearchPager,
     pub results: Vec<Dataset>,
     pub current_index: usize,
 }
@@ -29,184 +371,379 @@ impl VTab for DatenoSearchFunction {
     type InitData = DatenoSearchInitData;
     type BindData = DatenoSearchBindData;
 
-    fn parameters() -> Option<Vec<duckdb::types::LogicalTypeHandle>> {
+    // Only the columns a query references are passed to init
+    fn supports_pushdown() -> bool {
+        true
+    }
+
+    fn parameters() -> Option<Vec<LogicalTypeHandle>> {
         Some(vec![
-            LogicalType::Varchar.into(),                    // query (required)
-            LogicalType::List(Box::new(LogicalType::Varchar)).into(), // filters (optional)
-            LogicalType::Bigint.into(),                    // limit (optional)
+            LogicalTypeId::Varchar.into(),                  // query (required)
+            LogicalTypeId::Any.into(),                      // filters (optional): LIST, MAP or STRUCT
+            LogicalTypeId::Bigint.into(),                   // limit (optional)
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalTypeHandle)>> {
+        Some(vec![
+            ("filters".to_string(), LogicalTypeId::Any.into()),
+            ("limit".to_string(), LogicalTypeId::Bigint.into()),
+            ("offset".to_string(), LogicalTypeId::Bigint.into()),
+            ("sort".to_string(), LogicalTypeId::Varchar.into()),
+            ("cursor".to_string(), LogicalTypeId::Varchar.into()),
+            ("country".to_string(), LogicalTypeId::Varchar.into()),
+            ("format".to_string(), LogicalTypeId::Varchar.into()),
+            ("raw".to_string(), LogicalTypeId::Boolean.into()),
+            ("metadata".to_string(), LogicalTypeId::Boolean.into()),
+            ("strict".to_string(), LogicalTypeId::Boolean.into()),
         ])
     }
 
     fn bind(bind: &BindInfo) -> Result<Self::BindData, Box<dyn Error>> {
-        let param_count = bind.get_parameter_count();
+        let bind_data = DatenoSearchBindData::from_bind(bind)?;
+        Self::add_result_columns(bind, &bind_data);
+        Ok(bind_data)
+    }
 
-        // Get query parameter (required, first parameter)
-        if param_count == 0 {
-            bind.set_error("query parameter is required");
-            return Err("query parameter is required".into());
+    fn init(init: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
+        let bind_data = init_bind_data::<DatenoSearchBindData>(init)?;
+        let client = bind_data.client_config.client()?;
+
+        // Only materialise the columns the query references
+        let schema = bind_data.columns();
+        let columns: Vec<Column> = init
+            .get_column_indices()
+            .into_iter()
+            .filter_map(|index| schema.get(index as usize).copied())
+            .collect();
+
+        // Ask the API for just those fields
+        let mut fields: Vec<&str> = vec![Column::Id.name(), Column::Title.name()];
+        for name in columns.iter().filter_map(|c| c.api_field()) {
+            if !fields.contains(&name) {
+                fields.push(name);
+            }
+        }
+        // The raw record needs every field the API has
+        if fields.len() == Column::ALL.len() || columns.contains(&Column::Raw) {
+            fields.clear();
         }
 
-        let query_value = bind.get_parameter(0);
-        let query = match query_value {
-            Value::Text(s) => s,
-            _ => {
-                bind.set_error("query parameter must be a string");
-                return Err("query parameter must be a string".into());
-            }
-        };
+        // Pages are fetched lazily from `func` as DuckDB pulls rows
+        let pager = bind_data.pager(client.clone(), fields)?;
+
+        Ok(DatenoSearchInitData {
+            client,
+            columns,
+            state: Mutex::new(DatenoSearchScanState {
+                pager,
+                results: Vec::new(),
+                current_index: 0,
+            }),
+        })
+    }
 
-        // Get filters parameter (optional, second parameter)
-        let mut filters = Vec::new();
-        if param_count >= 2 {
-            let filters_value = bind.get_parameter(1);
-            if let Value::List(list) = filters_value {
-                for item in list {
-                    if let Value::Text(s) = item {
-                        filters.push(s);
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> Result<(), Box<dyn Error>> {
+        let init_data = func.get_init_data();
+
+        let mut state = init_data
+            .state
+            .lock()
+            .map_err(|_| "Scan state lock poisoned")?;
+
+        // Fill the output chunk from as many pages as fit in one vector
+        let mut row = 0;
+        let mut list_offsets = list_offsets(init_data.columns.len());
+        while row < STANDARD_VECTOR_SIZE {
+            if state.current_index >= state.results.len() {
+                match state
+                    .pager
+                    .next_page()
+                    .map_err(|e| format!("Search failed: {}", e))?
+                {
+                    Some(page) => {
+                        state.results = page;
+                        state.current_index = 0;
                     }
+                    None => break,
                 }
             }
-        }
 
-        // Get limit parameter (optional, third parameter)
-        let mut limit = 10i64; // default
-        if param_count >= 3 {
-            let limit_value = bind.get_parameter(2);
-            if let Value::BigInt(n) = limit_value {
-                limit = n;
+            let take = (STANDARD_VECTOR_SIZE - row).min(state.results.len() - state.current_index);
+            let start = state.current_index;
+            let page = PageInfo {
+                total_hits: state.pager.total(),
+                next_cursor: state.pager.next_cursor(),
+                skipped: state.pager.skipped(),
+            };
+            for dataset in &state.results[start..start + take] {
+                write_row(output, row, dataset, &page, &init_data.columns, &mut list_offsets);
+                row += 1;
             }
+            state.current_index += take;
         }
 
-        // Validate limit
-        if limit < 1 || limit > 100 {
-            bind.set_error("limit must be between 1 and 100");
-            return Err("limit must be between 1 and 100".into());
+        // An empty chunk tells DuckDB the scan is complete
+        output.set_len(row);
+
+        Ok(())
+    }
+}
+
+impl DatenoTableFunction for DatenoSearchFunction {
+    const NAME: &'static str = "dateno_search";
+
+    fn result_columns(bind_data: &Self::BindData) -> Vec<(&'static str, LogicalTypeHandle)> {
+        bind_data
+            .columns()
+            .iter()
+            .map(|column| (column.name(), column.logical_type()))
+            .collect()
+    }
+}
+
+/// Emit the single row held in `slot` on the first call, then an empty chunk
+/// to tell DuckDB the scan is complete. `write` fills row 0 of `output`.
+pub(crate) fn emit_once<T>(
+    slot: &Mutex<Option<T>>,
+    output: &mut DataChunkHandle,
+    write: impl FnOnce(T, &mut DataChunkHandle),
+) -> Result<(), Box<dyn Error>> {
+    let value = slot.lock().map_err(|_| "Scan state lock poisoned")?.take();
+    match value {
+        Some(value) => {
+            write(value, output);
+            output.set_len(1);
         }
+        None => output.set_len(0),
+    }
 
-        Ok(DatenoSearchBindData {
-            query,
-            filters,
-            limit,
-        })
+    Ok(())
+}
+
+/// Bind data set during the bind phase, for use in `init`
+pub(crate) fn init_bind_data<T>(init: &InitInfo) -> Result<&T, Box<dyn Error>> {
+    let bind_data_ptr = init.get_bind_data::<T>();
+    if bind_data_ptr.is_null() {
+        init.set_error("Failed to get bind data");
+        return Err("Failed to get bind data".into());
     }
 
-    fn init(init: &InitInfo) -> Result<Self::InitData, Box<dyn Error>> {
-        // Get bind data (read-only) - this is set during bind phase
-        let bind_data_ptr = init.get_bind_data::<DatenoSearchBindData>();
-        if bind_data_ptr.is_null() {
-            init.set_error("Failed to get bind data");
-            return Err("Failed to get bind data".into());
+    // SAFETY: bind_data is set during bind phase and remains valid until function completes
+    Ok(unsafe { &*bind_data_ptr })
+}
+
+/// Look up a parameter by name, falling back to its positional slot.
+///
+/// Named parameters take precedence so `limit := 50` works without the
+/// positional `filters` placeholder.
+pub(crate) fn get_parameter(bind: &BindInfo, index: u64, name: &str) -> Option<Value> {
+    if let Some(value) = bind.get_named_parameter(name) {
+        return Some(value);
+    }
+    positional_parameter(bind, index)
+}
+
+/// The positional parameter at `index`, if the call passed one
+pub(crate) fn positional_parameter(bind: &BindInfo, index: u64) -> Option<Value> {
+    if bind.get_parameter_count() > index {
+        return Some(bind.get_parameter(index));
+    }
+    None
+}
+
+/// Read the required `query` string, the first parameter of the search functions
+pub(crate) fn query_parameter(bind: &BindInfo) -> Result<String, Box<dyn Error>> {
+    match positional_parameter(bind, 0) {
+        Some(Value::Text(s)) => Ok(s),
+        Some(_) => {
+            bind.set_error("query parameter must be a string");
+            Err("query parameter must be a string".into())
         }
+        None => {
+            bind.set_error("query parameter is required");
+            Err("query parameter is required".into())
+        }
+    }
+}
 
-        // SAFETY: bind_data is set during bind phase and remains valid until function completes
-        let bind_data = unsafe { &*bind_data_ptr };
+/// Compile filters for functions that only query the API, such as counts and
+/// facets, where results are never seen and so cannot be filtered locally
+pub(crate) fn api_filters(function: &str, filters: &[Filter]) -> Result<Vec<String>, String> {
+    let compiled = filters::compile(filters).map_err(|e| e.to_string())?;
+    match compiled.local.first() {
+        Some(filter) => Err(format!(
+            "{} only supports = filters on the API's filter fields, combined with AND; cannot apply: {}",
+            function, filter
+        )),
+        None => Ok(compiled.api),
+    }
+}
 
-        // Get API key from environment
-        let api_key = std::env::var("DATENO_API_KEY").ok();
-        let client = Arc::new(
-            DatenoClient::new(api_key)
-                .map_err(|e| format!("Failed to create client: {}", e))?,
-        );
+/// Values shared by every row of one API page
+#[derive(Debug, Default)]
+pub(crate) struct PageInfo<'a> {
+    /// The query's hit count reported by the API, if known
+    pub total_hits: Option<usize>,
+    /// Cursor for the following page, if the API provides one
+    pub next_cursor: Option<&'a str>,
+    /// Malformed results dropped under `strict := false` so far
+    pub skipped: u64,
+}
 
-        // Execute search
-        let request = SearchRequest {
-            query: bind_data.query.clone(),
-            filters: bind_data.filters.clone(),
-            limit: bind_data.limit,
-        };
+/// Write the projected columns of one dataset into row `row` of the output chunk.
+///
+/// `page` carries the values shared by every row of the API page the dataset came from.
+/// `list_offsets` tracks, per output column, how many entries each list child
+/// vector already holds for this chunk.
+pub(crate) fn write_row(
+    output: &mut DataChunkHandle,
+    row: usize,
+    dataset: &Dataset,
+    page: &PageInfo,
+    columns: &[Column],
+    list_offsets: &mut ListOffsets,
+) {
+    for (index, column) in columns.iter().enumerate() {
+        match column {
+            Column::Id => output.flat_vector(index).insert(row, dataset.id.as_str()),
+            Column::Title => output.flat_vector(index).insert(row, dataset.title.as_str()),
+            Column::Description => output.flat_vector(index).insert(row, dataset.description.as_str()),
+            Column::Format => output.flat_vector(index).insert(row, dataset.format.as_str()),
+            Column::Source => {
+                let source = &dataset.source;
+                let offsets = &mut list_offsets[index];
+                let vector = output.struct_vector(index);
+                vector.child(0, STANDARD_VECTOR_SIZE).insert(row, source.catalog_name.as_str());
+                vector.child(1, STANDARD_VECTOR_SIZE).insert(row, source.catalog_url.as_str());
+                vector.child(2, STANDARD_VECTOR_SIZE).insert(row, source.software.as_str());
+                write_entities(&mut vector.list_vector_child(3), row, &mut offsets[0], &source.countries);
+                write_entities(&mut vector.list_vector_child(4), row, &mut offsets[1], &source.languages);
+                write_entities(&mut vector.list_vector_child(5), row, &mut offsets[2], &source.organizations);
+            }
+            Column::Url => output.flat_vector(index).insert(row, dataset.url.as_str()),
+            Column::CreatedAt => write_timestamp(&mut output.flat_vector(index), row, dataset.created_at),
+            Column::UpdatedAt => write_timestamp(&mut output.flat_vector(index), row, dataset.updated_at),
+            Column::Formats => {
+                write_strings(&mut output.list_vector(index), row, &mut list_offsets[index][0], &dataset.formats)
+            }
+            Column::Resources => {
+                write_resources(&mut output.list_vector(index), row, &mut list_offsets[index][0], &dataset.resources)
+            }
+            Column::Raw => match &dataset.raw {
+                Some(raw) => output.flat_vector(index).insert(row, raw.to_string().as_str()),
+                None => output.flat_vector(index).set_null(row),
+            },
+            Column::TotalHits => match page.total_hits {
+                Some(total) => output.flat_vector(index).as_mut_slice::<i64>()[row] = total as i64,
+                None => output.flat_vector(index).set_null(row),
+            },
+            Column::Score => match dataset.score {
+                Some(score) => output.flat_vector(index).as_mut_slice::<f64>()[row] = score,
+                None => output.flat_vector(index).set_null(row),
+            },
+            Column::Warnings => {
+                write_strings(&mut output.list_vector(index), row, &mut list_offsets[index][0], &dataset.warnings)
+            }
+            Column::NextCursor => match page.next_cursor {
+                Some(cursor) => output.flat_vector(index).insert(row, cursor),
+                None => output.flat_vector(index).set_null(row),
+            },
+            Column::Skipped => output.flat_vector(index).as_mut_slice::<i64>()[row] = page.skipped as i64,
+        }
+    }
+}
 
-        let response = client
-            .search(request)
-            .map_err(|e| format!("Search failed: {}", e))?;
+/// Append `items` to a `LIST(VARCHAR)` vector as the entry for `row`
+fn write_strings(list: &mut ListVector, row: usize, offset: &mut usize, items: &[String]) {
+    let child = list.child(*offset + items.len());
+    for (i, item) in items.iter().enumerate() {
+        child.insert(*offset + i, item.as_str());
+    }
+    list.set_entry(row, *offset, items.len());
+    *offset += items.len();
+    list.set_len(*offset);
+}
 
-        Ok(DatenoSearchInitData {
-            client,
-            results: response.results,
-            current_index: 0,
-        })
+/// `STRUCT(catalog_name, catalog_url, software, countries, languages, organizations)`
+/// where each list holds `STRUCT(id, name)` entries
+fn source_type() -> LogicalTypeHandle {
+    let entities = || {
+        LogicalTypeHandle::list(&LogicalTypeHandle::struct_type(&[
+            ("id", LogicalTypeId::Varchar.into()),
+            ("name", LogicalTypeId::Varchar.into()),
+        ]))
+    };
+    LogicalTypeHandle::struct_type(&[
+        ("catalog_name", LogicalTypeId::Varchar.into()),
+        ("catalog_url", LogicalTypeId::Varchar.into()),
+        ("software", LogicalTypeId::Varchar.into()),
+        ("countries", entities()),
+        ("languages", entities()),
+        ("organizations", entities()),
+    ])
+}
+
+/// Append `entities` to a `LIST(STRUCT(id, name))` vector as the entry for `row`
+fn write_entities(list: &mut ListVector, row: usize, offset: &mut usize, entities: &[SourceEntity]) {
+    let capacity = *offset + entities.len();
+    let child: StructVector = list.struct_child(capacity);
+    let ids = child.child(0, capacity);
+    let names = child.child(1, capacity);
+    for (i, entity) in entities.iter().enumerate() {
+        ids.insert(*offset + i, entity.id.as_str());
+        names.insert(*offset + i, entity.name.as_str());
     }
+    list.set_entry(row, *offset, entities.len());
+    *offset = capacity;
+    list.set_len(*offset);
+}
 
-    fn func(
-        func: &TableFunctionInfo<Self>,
-        output: &mut DataChunkHandle,
-    ) -> Result<(), Box<dyn Error>> {
-        use duckdb::DataChunk;
-        
-        // Get init data
-        let init_data = func
-            .get_init_data::<Self::InitData>()
-            .ok_or("Failed to get init data")?;
-
-        let results = &init_data.results;
-
-        if results.is_empty() {
-            // No rows to output
-            return Ok(());
-        }
-
-        // Create a DataChunk with all results
-        let mut chunk = DataChunk::new(9); // 9 columns
-
-        for dataset in results {
-            let mut row = Vec::new();
-
-            // id
-            row.push(Value::Text(dataset.id.clone()));
-
-            // title
-            row.push(Value::Text(dataset.title.clone()));
-
-            // description
-            row.push(Value::Text(dataset.description.clone()));
-
-            // format
-            row.push(Value::Text(dataset.format.clone()));
-
-            // source
-            row.push(Value::Text(dataset.source.clone()));
-
-            // url
-            row.push(Value::Text(dataset.url.clone()));
-
-            // created_at
-            row.push(
-                dataset
-                    .created_at
-                    .map(|dt| {
-                        Value::Timestamp(duckdb::types::Timestamp::from_micros(
-                            dt.timestamp_micros(),
-                        ))
-                    })
-                    .unwrap_or(Value::Null),
-            );
-
-            // updated_at
-            row.push(
-                dataset
-                    .updated_at
-                    .map(|dt| {
-                        Value::Timestamp(duckdb::types::Timestamp::from_micros(
-                            dt.timestamp_micros(),
-                        ))
-                    })
-                    .unwrap_or(Value::Null),
-            );
-
-            // formats
-            let formats: Vec<Value> = dataset
-                .formats
-                .iter()
-                .map(|f| Value::Text(f.clone()))
-                .collect();
-            row.push(Value::List(formats));
-
-            chunk.append_row(&row)?;
-        }
-
-        // Write chunk to output
-        // Note: This API may need adjustment based on actual duckdb-rs implementation
-        output.append_data_chunk(&chunk)?;
+/// `STRUCT(name, url, format, mimetype, size, last_modified)` describing one file
+fn resource_type() -> LogicalTypeHandle {
+    LogicalTypeHandle::struct_type(&[
+        ("name", LogicalTypeId::Varchar.into()),
+        ("url", LogicalTypeId::Varchar.into()),
+        ("format", LogicalTypeId::Varchar.into()),
+        ("mimetype", LogicalTypeId::Varchar.into()),
+        ("size", LogicalTypeId::Bigint.into()),
+        ("last_modified", LogicalTypeId::Timestamp.into()),
+    ])
+}
 
-        Ok(())
+/// Append `resources` to a `LIST(resource_type())` vector as the entry for `row`
+fn write_resources(list: &mut ListVector, row: usize, offset: &mut usize, resources: &[Resource]) {
+    let capacity = *offset + resources.len();
+    let child: StructVector = list.struct_child(capacity);
+    for (i, resource) in resources.iter().enumerate() {
+        write_resource(&child, *offset + i, capacity, resource);
+    }
+    list.set_entry(row, *offset, resources.len());
+    *offset = capacity;
+    list.set_len(*offset);
+}
+
+/// Write one resource into row `row` of a `resource_type()` struct vector
+fn write_resource(vector: &StructVector, row: usize, capacity: usize, resource: &Resource) {
+    vector.child(0, capacity).insert(row, resource.name.as_str());
+    vector.child(1, capacity).insert(row, resource.url.as_str());
+    vector.child(2, capacity).insert(row, resource.format.as_str());
+    vector.child(3, capacity).insert(row, resource.mimetype.as_str());
+    let mut size = vector.child(4, capacity);
+    match resource.size {
+        Some(bytes) => size.as_mut_slice::<i64>()[row] = bytes as i64,
+        None => size.set_null(row),
+    }
+    write_timestamp(&mut vector.child(5, capacity), row, resource.last_modified);
+}
+
+/// Write a timestamp as microseconds since the epoch, or NULL when absent
+pub(crate) fn write_timestamp(vector: &mut FlatVector, row: usize, value: Option<DateTime<Utc>>) {
+    match value {
+        Some(dt) => vector.as_mut_slice::<i64>()[row] = dt.timestamp_micros(),
+        None => vector.set_null(row),
     }
 }
@@ -0,0 +1,144 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+/// Naive datetime layouts seen in catalog metadata, interpreted as UTC
+const NAIVE_DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+];
+
+/// Offset-qualified layouts that RFC 3339 rejects (`+0000`, space separator)
+const OFFSET_DATETIME_FORMATS: &[&str] = &["%Y-%m-%dT%H:%M:%S%.f%z", "%Y-%m-%d %H:%M:%S%.f%z"];
+
+/// Epoch values above this are taken to be milliseconds rather than seconds
+/// (1e11 seconds is in the year 5138)
+const EPOCH_MILLIS_THRESHOLD: f64 = 1e11;
+
+/// Parse a timestamp from a JSON value, accepting the formats catalogs
+/// commonly publish. Returns `None` for anything unrecognised.
+pub fn parse_value(value: &serde_json::Value) -> Option<DateTime<Utc>> {
+    match value {
+        serde_json::Value::String(s) => parse(s),
+        serde_json::Value::Number(n) => n.as_f64().and_then(from_epoch),
+        _ => None,
+    }
+}
+
+/// Parse a timestamp string: RFC 3339, ISO 8601 without offset or seconds,
+/// date only (`2021-05-03` or `20210503`), year and month (`2021-05`), year
+/// (`2021`), or epoch seconds/milliseconds. Values without an offset are
+/// taken as UTC.
+pub fn parse(s: &str) -> Option<DateTime<Utc>> {
+    let s = s.trim();
+    if s.is_empty() {
+        return None;
+    }
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    for format in OFFSET_DATETIME_FORMATS {
+        if let Ok(dt) = DateTime::parse_from_str(s, format) {
+            return Some(dt.with_timezone(&Utc));
+        }
+    }
+    for format in NAIVE_DATETIME_FORMATS {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(s, format) {
+            return Some(Utc.from_utc_datetime(&dt));
+        }
+    }
+
+    // Compact dates; checked before epochs so that "20210503" is read as
+    // a date, not a second count in 1970
+    if let Some(dt) = parse_compact_date(s) {
+        return Some(dt);
+    }
+
+    // Epoch numbers sent as strings; checked before partial dates so that
+    // "2021" is read as a year, not a second count
+    if s.len() > 4 {
+        if let Ok(n) = s.parse::<f64>() {
+            return from_epoch(n);
+        }
+    }
+
+    parse_partial_date(s)
+}
+
+/// `2021-05-03`, `2021-05` or `2021`, at midnight UTC on the first day covered
+fn parse_partial_date(s: &str) -> Option<DateTime<Utc>> {
+    let mut parts = s.splitn(3, '-');
+    let year = parts.next()?;
+    if year.len() != 4 {
+        return None;
+    }
+    let year: i32 = year.parse().ok()?;
+    let month: u32 = match parts.next() {
+        Some(m) => m.parse().ok()?,
+        None => 1,
+    };
+    let day: u32 = match parts.next() {
+        Some(d) => d.parse().ok()?,
+        None => 1,
+    };
+    let date = NaiveDate::from_ymd_opt(year, month, day)?;
+    Some(Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0)?))
+}
+
+/// `20210503`, at midnight UTC
+fn parse_compact_date(s: &str) -> Option<DateTime<Utc>> {
+    if s.len() != 8 || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let date = NaiveDate::from_ymd_opt(s[..4].parse().ok()?, s[4..6].parse().ok()?, s[6..].parse().ok()?)?;
+    Some(Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0)?))
+}
+
+/// Epoch seconds, or milliseconds for values too large to be seconds
+fn from_epoch(n: f64) -> Option<DateTime<Utc>> {
+    if !n.is_finite() {
+        return None;
+    }
+    let millis = if n.abs() >= EPOCH_MILLIS_THRESHOLD { n } else { n * 1000.0 };
+    DateTime::from_timestamp_millis(millis as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(s: &str) -> Option<DateTime<Utc>> {
+        Some(DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc))
+    }
+
+    #[test]
+    fn test_parse_common_formats() {
+        assert_eq!(parse("2021-05-03T10:20:30+02:00"), utc("2021-05-03T08:20:30Z"));
+        assert_eq!(parse("2021-05-03T10:20:30+0200"), utc("2021-05-03T08:20:30Z"));
+        assert_eq!(parse("2021-05-03T10:20:30.5"), utc("2021-05-03T10:20:30.5Z"));
+        assert_eq!(parse("2021-05-03 10:20:30"), utc("2021-05-03T10:20:30Z"));
+        assert_eq!(parse("2021-05-03T10:20"), utc("2021-05-03T10:20:00Z"));
+        assert_eq!(parse("2021-05-03"), utc("2021-05-03T00:00:00Z"));
+        assert_eq!(parse("20210503"), utc("2021-05-03T00:00:00Z"));
+        assert_eq!(parse("2021-05"), utc("2021-05-01T00:00:00Z"));
+        assert_eq!(parse("2021"), utc("2021-01-01T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_parse_epoch() {
+        assert_eq!(parse("1620000000"), utc("2021-05-03T00:00:00Z"));
+        // Eight digits that are not a valid date are still epoch seconds
+        assert_eq!(parse("99999999"), utc("1973-03-03T09:46:39Z"));
+        assert_eq!(parse_value(&serde_json::json!(1620000000)), utc("2021-05-03T00:00:00Z"));
+        assert_eq!(parse_value(&serde_json::json!(1620000000123u64)), utc("2021-05-03T00:00:00.123Z"));
+    }
+
+    #[test]
+    fn test_parse_rejects_garbage() {
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("yesterday"), None);
+        assert_eq!(parse("2021-13-01"), None);
+        assert_eq!(parse_value(&serde_json::json!(true)), None);
+    }
+}
@@ -1,96 +1,824 @@
-use dateno_duckdb_ext::api::{DatenoClient, SearchRequest};
-use mockito::{Mock, Server};
+use dateno_duckdb_ext::DatenoError;
+use dateno_duckdb_ext::api::{DatenoClient, RetryPolicy, SearchPager, SearchRequest};
+use dateno_duckdb_ext::cache::{CachePolicy, DiskCache, DiskCacheMode};
+use dateno_duckdb_ext::filters;
+use mockito::{Matcher, Server};
+use std::sync::Arc;
+use std::time::Duration;
 
 #[test]
 fn test_search_request_basic() {
     let mut server = Server::new();
-    
+
     // Mock API response
-    let mock = Mock::new()
-        .expect_method("GET")
-        .expect_path("/search/0.2/query")
-        .expect_query_param("q", "climate")
-        .expect_query_param("limit", "10")
-        .expect_header("authorization", "Bearer test-key")
-        .respond_with(200)
-        .body(r#"{"total": 1, "results": [{"id": "test-1", "title": "Test Dataset", "description": "", "format": "CSV", "source": "", "url": "", "formats": []}]}"#)
-        .create_on(&mut server);
-
-    let client = DatenoClient::new(Some("test-key".to_string())).unwrap();
+    let mock = server
+        .mock("GET", "/search/0.2/query")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("q".into(), "climate".into()),
+            Matcher::UrlEncoded("limit".into(), "10".into()),
+        ]))
+        .match_header("authorization", "Bearer test-key")
+        .with_status(200)
+        .with_body(r#"{"total": 1, "results": [{"id": "test-1", "title": "Test Dataset", "description": "", "format": "CSV", "source": "", "url": "", "formats": []}]}"#)
+        .create();
+
+    let client = DatenoClient::new(&server.url(), Some("test-key".to_string())).unwrap();
     let request = SearchRequest {
         query: "climate".to_string(),
         filters: vec![],
         limit: 10,
+        ..Default::default()
     };
 
     let response = client.search(request);
     assert!(response.is_ok());
-    
+
     let search_response = response.unwrap();
     assert_eq!(search_response.total, 1);
     assert_eq!(search_response.results.len(), 1);
     assert_eq!(search_response.results[0].id, "test-1");
     assert_eq!(search_response.results[0].title, "Test Dataset");
-    
+
     mock.assert();
 }
 
 #[test]
 fn test_search_with_filters() {
     let mut server = Server::new();
-    
-    let mock = Mock::new()
-        .expect_method("GET")
-        .expect_path("/search/0.2/query")
-        .expect_query_param("q", "temperature")
-        .expect_query_param("limit", "50")
-        .expect_query_param("filters", "\"source.countries.name\"=\"Canada\"")
-        .respond_with(200)
-        .body(r#"{"total": 0, "results": []}"#)
-        .create_on(&mut server);
 
-    let client = DatenoClient::new(None).unwrap();
+    let mock = server
+        .mock("GET", "/search/0.2/query")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("q".into(), "temperature".into()),
+            Matcher::UrlEncoded("limit".into(), "50".into()),
+            Matcher::UrlEncoded("filters".into(), "\"source.countries.name\"=\"Canada\"".into()),
+        ]))
+        .with_status(200)
+        .with_body(r#"{"total": 0, "results": []}"#)
+        .create();
+
+    let client = DatenoClient::new(&server.url(), None).unwrap();
     let request = SearchRequest {
         query: "temperature".to_string(),
         filters: vec!["source.countries.name=Canada".to_string()],
         limit: 50,
+        ..Default::default()
     };
 
     let response = client.search(request);
     assert!(response.is_ok());
-    
+
     let search_response = response.unwrap();
     assert_eq!(search_response.total, 0);
-    
+
+    mock.assert();
+}
+
+#[test]
+fn test_search_escapes_quotes_in_filters() {
+    let mut server = Server::new();
+
+    // The embedded quotes must not end the value and inject a second filter
+    let mock = server
+        .mock("GET", "/search/0.2/query")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("q".into(), "escaping".into()),
+            Matcher::UrlEncoded(
+                "filters".into(),
+                r#""source.organizations.name"="Bureau \"Stats\"\",\"format\"=\"CSV""#.into(),
+            ),
+        ]))
+        .with_status(200)
+        .with_body(r#"{"total": 0, "results": []}"#)
+        .create();
+
+    let client = DatenoClient::new(&server.url(), None).unwrap();
+    let request = SearchRequest {
+        query: "escaping".to_string(),
+        filters: vec![r#"source.organizations.name=Bureau "Stats"","format"="CSV"#.to_string()],
+        limit: 10,
+        ..Default::default()
+    };
+
+    assert!(client.search(request).is_ok());
+    mock.assert();
+}
+
+#[test]
+fn test_search_rejects_invalid_filter_keys() {
+    let client = DatenoClient::new("http://localhost:1", None).unwrap();
+    let request = SearchRequest {
+        query: "climate".to_string(),
+        filters: vec![r#"format"="CSV"#.to_string()],
+        limit: 10,
+        ..Default::default()
+    };
+
+    assert!(matches!(client.search(request), Err(DatenoError::InvalidFilter(_))));
+}
+
+#[test]
+fn test_search_base_url_with_path_prefix() {
+    let mut server = Server::new();
+
+    let mock = server
+        .mock("GET", "/mirror/search/0.2/query")
+        .match_query(Matcher::UrlEncoded("q".into(), "climate".into()))
+        .with_status(200)
+        .with_body(r#"{"total": 0, "results": []}"#)
+        .create();
+
+    let base_url = format!("{}/mirror", server.url());
+    let client = DatenoClient::new(&base_url, None).unwrap();
+    let request = SearchRequest {
+        query: "climate".to_string(),
+        limit: 10,
+        ..Default::default()
+    };
+
+    assert!(client.search(request).is_ok());
+    mock.assert();
+}
+
+#[test]
+fn test_search_sends_offset_and_sort() {
+    let mut server = Server::new();
+
+    let mock = server
+        .mock("GET", "/search/0.2/query")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("offset".into(), "100".into()),
+            Matcher::UrlEncoded("sort".into(), "updated_at desc".into()),
+        ]))
+        .with_status(200)
+        .with_body(r#"{"total": 0, "results": []}"#)
+        .create();
+
+    let client = DatenoClient::new(&server.url(), None).unwrap();
+    let request = SearchRequest {
+        query: "climate".to_string(),
+        limit: 10,
+        offset: 100,
+        sort: Some("updated_at desc".to_string()),
+        ..Default::default()
+    };
+
+    assert!(client.search(request).is_ok());
+    mock.assert();
+}
+
+#[test]
+fn test_search_requests_selected_fields() {
+    let mut server = Server::new();
+
+    let mock = server
+        .mock("GET", "/search/0.2/query")
+        .match_query(Matcher::UrlEncoded("fields".into(), "id,title,format".into()))
+        .with_status(200)
+        .with_body(r#"{"total": 1, "results": [{"id": "a", "title": "A", "format": "CSV"}]}"#)
+        .create();
+
+    let client = DatenoClient::new(&server.url(), None).unwrap();
+    let request = SearchRequest {
+        query: "climate".to_string(),
+        limit: 10,
+        fields: vec!["id".to_string(), "title".to_string(), "format".to_string()],
+        ..Default::default()
+    };
+
+    let response = client.search(request).unwrap();
+    assert_eq!(response.results[0].format, "CSV");
     mock.assert();
 }
 
+#[test]
+fn test_pager_walks_offsets_until_limit() {
+    let mut server = Server::new();
+
+    let page = |n: usize, start: usize| {
+        let results: Vec<String> = (start..start + n)
+            .map(|i| format!(r#"{{"id": "d{}", "title": "Dataset {}"}}"#, i, i))
+            .collect();
+        format!(r#"{{"total": 1000, "results": [{}]}}"#, results.join(","))
+    };
+
+    let first = server
+        .mock("GET", "/search/0.2/query")
+        .match_query(Matcher::UrlEncoded("limit".into(), "100".into()))
+        .with_status(200)
+        .with_body(page(100, 0))
+        .create();
+    let second = server
+        .mock("GET", "/search/0.2/query")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("limit".into(), "50".into()),
+            Matcher::UrlEncoded("offset".into(), "100".into()),
+        ]))
+        .with_status(200)
+        .with_body(page(50, 100))
+        .create();
+
+    let client = Arc::new(DatenoClient::new(&server.url(), None).unwrap());
+    let request = SearchRequest {
+        query: "climate".to_string(),
+        ..Default::default()
+    };
+    let mut pager = SearchPager::new(client, request, Some(150));
+
+    let mut ids = Vec::new();
+    while let Some(results) = pager.next_page().unwrap() {
+        ids.extend(results.into_iter().map(|d| d.id));
+    }
+
+    assert_eq!(ids.len(), 150);
+    assert_eq!(ids[100], "d100");
+    assert_eq!(pager.total(), Some(1000));
+    first.assert();
+    second.assert();
+}
+
+#[test]
+fn test_pager_applies_local_filters_before_limit() {
+    let mut server = Server::new();
+
+    // Every tenth dataset was updated in 2023
+    let page = |start: usize| {
+        let results: Vec<String> = (start..start + 100)
+            .map(|i| {
+                let year = if i % 10 == 0 { 2023 } else { 2019 };
+                format!(r#"{{"id": "d{}", "title": "Dataset {}", "updated_at": "{}-06-01"}}"#, i, i, year)
+            })
+            .collect();
+        format!(r#"{{"total": 1000, "results": [{}]}}"#, results.join(","))
+    };
+
+    let first = server
+        .mock("GET", "/search/0.2/query")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("q".into(), "recent".into()),
+            Matcher::UrlEncoded("limit".into(), "100".into()),
+        ]))
+        .with_status(200)
+        .with_body(page(0))
+        .create();
+    let second = server
+        .mock("GET", "/search/0.2/query")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("q".into(), "recent".into()),
+            Matcher::UrlEncoded("offset".into(), "100".into()),
+        ]))
+        .with_status(200)
+        .with_body(page(100))
+        .create();
+
+    let client = Arc::new(DatenoClient::new(&server.url(), None).unwrap());
+    let request = SearchRequest {
+        query: "recent".to_string(),
+        ..Default::default()
+    };
+    let filter = filters::parse("updated_at >= 2023-01-01").unwrap();
+    let mut pager = SearchPager::new(client, request, Some(12)).with_local_filters(vec![filter]);
+
+    let mut ids = Vec::new();
+    while let Some(results) = pager.next_page().unwrap() {
+        ids.extend(results.into_iter().map(|d| d.id));
+    }
+
+    // The limit counts matching rows, so a second page is needed
+    assert_eq!(ids.len(), 12);
+    assert_eq!(ids[0], "d0");
+    assert_eq!(ids[11], "d110");
+    first.assert();
+    second.assert();
+}
+
+#[test]
+fn test_pager_caps_locally_filtered_pages() {
+    let mut server = Server::new();
+
+    let results: Vec<String> = (0..100)
+        .map(|i| format!(r#"{{"id": "d{}", "title": "Dataset {}", "format": "JSON"}}"#, i, i))
+        .collect();
+    let mock = server
+        .mock("GET", "/search/0.2/query")
+        .match_query(Matcher::UrlEncoded("q".into(), "scan cap".into()))
+        .with_status(200)
+        .with_body(format!(r#"{{"total": 100000, "results": [{}]}}"#, results.join(",")))
+        .expect(2)
+        .create();
+
+    let client = Arc::new(DatenoClient::new(&server.url(), None).unwrap());
+    let request = SearchRequest {
+        query: "scan cap".to_string(),
+        ..Default::default()
+    };
+    let filter = filters::parse("format IN (CSV, Parquet)").unwrap();
+    let mut pager = SearchPager::new(client, request, Some(10))
+        .with_local_filters(vec![filter])
+        .with_max_scan_pages(2);
+
+    // No result passes, so the walk stops at the cap instead of reading every page
+    assert!(pager.next_page().unwrap().unwrap().is_empty());
+    assert!(pager.next_page().unwrap().unwrap().is_empty());
+    let err = pager.next_page().unwrap_err();
+    assert!(matches!(err, DatenoError::ScanLimit { pages: 2 }));
+    assert!(err.to_string().contains("= filters"));
+    mock.assert();
+}
+
+#[test]
+fn test_pager_stops_at_total() {
+    let mut server = Server::new();
+
+    let mock = server
+        .mock("GET", "/search/0.2/query")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_body(r#"{"total": 2, "results": [{"id": "a", "title": "A"}, {"id": "b", "title": "B"}]}"#)
+        .expect(1)
+        .create();
+
+    let client = Arc::new(DatenoClient::new(&server.url(), None).unwrap());
+    let request = SearchRequest {
+        query: "climate".to_string(),
+        ..Default::default()
+    };
+    let mut pager = SearchPager::new(client, request, None);
+
+    assert_eq!(pager.next_page().unwrap().map(|r| r.len()), Some(2));
+    assert!(pager.next_page().unwrap().is_none());
+    mock.assert();
+}
+
+#[test]
+fn test_pager_follows_cursor() {
+    let mut server = Server::new();
+
+    let page = |start: usize, next: Option<&str>| {
+        let results: Vec<String> = (start..start + 100)
+            .map(|i| format!(r#"{{"id": "d{}", "title": "Dataset {}"}}"#, i, i))
+            .collect();
+        let next = next.map_or("null".to_string(), |c| format!(r#""{}""#, c));
+        format!(r#"{{"total": 1000, "next_cursor": {}, "results": [{}]}}"#, next, results.join(","))
+    };
+
+    let first = server
+        .mock("GET", "/search/0.2/query")
+        .match_query(Matcher::UrlEncoded("cursor".into(), "c1".into()))
+        .with_status(200)
+        .with_body(page(100, Some("c2")))
+        .expect(1)
+        .create();
+    let second = server
+        .mock("GET", "/search/0.2/query")
+        .match_query(Matcher::UrlEncoded("cursor".into(), "c2".into()))
+        .with_status(200)
+        .with_body(page(200, None))
+        .expect(1)
+        .create();
+
+    let client = Arc::new(DatenoClient::new(&server.url(), None).unwrap());
+    let request = SearchRequest {
+        query: "climate".to_string(),
+        cursor: Some("c1".to_string()),
+        ..Default::default()
+    };
+    let mut pager = SearchPager::new(client, request, None);
+
+    assert_eq!(pager.next_page().unwrap().unwrap()[0].id, "d100");
+    assert_eq!(pager.next_cursor(), Some("c2"));
+    assert_eq!(pager.next_page().unwrap().unwrap()[0].id, "d200");
+    assert_eq!(pager.next_cursor(), None);
+
+    // No next cursor ends the walk even though `total` is not reached
+    assert!(pager.next_page().unwrap().is_none());
+    first.assert();
+    second.assert();
+}
+
+#[test]
+fn test_pager_drops_cursor_after_truncated_page() {
+    let mut server = Server::new();
+
+    let mock = server
+        .mock("GET", "/search/0.2/query")
+        .match_query(Matcher::UrlEncoded("cursor".into(), "truncated".into()))
+        .with_status(200)
+        .with_body(
+            r#"{"total": 10, "next_cursor": "c2", "results": [{"id": "a", "title": "A"}, {"id": "b", "title": "B"}]}"#,
+        )
+        .expect(1)
+        .create();
+
+    let client = Arc::new(DatenoClient::new(&server.url(), None).unwrap());
+    let request = SearchRequest {
+        query: "climate".to_string(),
+        cursor: Some("truncated".to_string()),
+        ..Default::default()
+    };
+    let mut pager = SearchPager::new(client, request, Some(1));
+
+    // "b" is cut off by the limit, and "c2" would resume after it
+    let page = pager.next_page().unwrap().unwrap();
+    assert_eq!(page.len(), 1);
+    assert_eq!(pager.next_cursor(), None);
+    assert!(pager.next_page().unwrap().is_none());
+    mock.assert();
+}
+
+#[test]
+fn test_pager_skips_malformed_records() {
+    let mut server = Server::new();
+
+    let first = server
+        .mock("GET", "/search/0.2/query")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("q".into(), "skip malformed".into()),
+            Matcher::UrlEncoded("limit".into(), "2".into()),
+        ]))
+        .with_status(200)
+        .with_body(r#"{"total": 10, "results": [{"id": "a"}, {"id": "b", "title": "B"}]}"#)
+        .expect(1)
+        .create();
+    let second = server
+        .mock("GET", "/search/0.2/query")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("q".into(), "skip malformed".into()),
+            Matcher::UrlEncoded("limit".into(), "1".into()),
+            Matcher::UrlEncoded("offset".into(), "2".into()),
+        ]))
+        .with_status(200)
+        .with_body(r#"{"total": 10, "results": [{"id": "c", "title": "C"}]}"#)
+        .expect(1)
+        .create();
+
+    let client = Arc::new(DatenoClient::new(&server.url(), None).unwrap());
+    let request = SearchRequest {
+        query: "skip malformed".to_string(),
+        skip_malformed: true,
+        ..Default::default()
+    };
+    let mut pager = SearchPager::new(client, request, Some(2));
+
+    let page = pager.next_page().unwrap().unwrap();
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].id, "b");
+    assert_eq!(pager.skipped(), 1);
+
+    // The dropped record is made up by the next page, like a filtered one
+    let page = pager.next_page().unwrap().unwrap();
+    assert_eq!(page[0].id, "c");
+    assert!(pager.next_page().unwrap().is_none());
+    assert_eq!(pager.skipped(), 1);
+    first.assert();
+    second.assert();
+}
+
 #[test]
 fn test_search_limit_validation() {
-    let client = DatenoClient::new(None).unwrap();
-    
+    let client = DatenoClient::new("http://localhost:1", None).unwrap();
+
     // Test limit too low
     let request = SearchRequest {
         query: "test".to_string(),
         filters: vec![],
         limit: 0,
+        ..Default::default()
     };
     assert!(client.search(request).is_err());
-    
-    // Test limit too high
+
+    // Test limit too high for a single page
     let request = SearchRequest {
         query: "test".to_string(),
         filters: vec![],
         limit: 101,
+        ..Default::default()
     };
     assert!(client.search(request).is_err());
-    
-    // Test valid limit
+}
+
+fn fast_retries(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_attempts,
+        base_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(10),
+        jitter: false,
+    }
+}
+
+#[test]
+fn test_search_retries_transient_errors() {
+    let mut server = Server::new();
+
+    let unavailable = server
+        .mock("GET", "/search/0.2/query")
+        .match_query(Matcher::Any)
+        .with_status(503)
+        .with_header("retry-after", "0")
+        .expect(2)
+        .create();
+
+    // mockito serves mocks that are still missing expected hits first, so
+    // this one only answers once both 503 responses have been sent
+    let ok = server
+        .mock("GET", "/search/0.2/query")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_body(r#"{"total": 0, "results": []}"#)
+        .create();
+
+    let client = DatenoClient::new(&server.url(), None)
+        .unwrap()
+        .with_retry_policy(fast_retries(3));
     let request = SearchRequest {
-        query: "test".to_string(),
-        filters: vec![],
-        limit: 50,
+        query: "climate".to_string(),
+        limit: 10,
+        ..Default::default()
+    };
+
+    assert!(client.search(request).is_ok());
+    unavailable.assert();
+    ok.assert();
+}
+
+#[test]
+fn test_search_reports_attempts_when_retries_exhausted() {
+    let mut server = Server::new();
+
+    let mock = server
+        .mock("GET", "/search/0.2/query")
+        .match_query(Matcher::Any)
+        .with_status(429)
+        .expect(3)
+        .create();
+
+    let client = DatenoClient::new(&server.url(), None)
+        .unwrap()
+        .with_retry_policy(fast_retries(3));
+    let request = SearchRequest {
+        query: "climate".to_string(),
+        limit: 10,
+        ..Default::default()
+    };
+
+    let err = client.search(request).unwrap_err();
+    assert!(err.to_string().contains("after 3 attempts"), "{}", err);
+    assert_eq!(err.status(), Some(429));
+    mock.assert();
+}
+
+#[test]
+fn test_search_does_not_retry_client_errors() {
+    let mut server = Server::new();
+
+    let mock = server
+        .mock("GET", "/search/0.2/query")
+        .match_query(Matcher::Any)
+        .with_status(400)
+        .expect(1)
+        .create();
+
+    let client = DatenoClient::new(&server.url(), None)
+        .unwrap()
+        .with_retry_policy(fast_retries(3));
+    let request = SearchRequest {
+        query: "climate".to_string(),
+        limit: 10,
+        ..Default::default()
+    };
+
+    assert!(client.search(request).is_err());
+    mock.assert();
+}
+
+#[test]
+fn test_search_unauthorized_error() {
+    let mut server = Server::new();
+
+    let mock = server
+        .mock("GET", "/search/0.2/query")
+        .match_query(Matcher::Any)
+        .with_status(401)
+        .with_body(r#"{"detail": "Invalid API key"}"#)
+        .create();
+
+    let client = DatenoClient::new(&server.url(), Some("bad-key".to_string())).unwrap();
+    let request = SearchRequest {
+        query: "climate".to_string(),
+        limit: 10,
+        ..Default::default()
+    };
+
+    let err = client.search(request).unwrap_err();
+    assert!(matches!(err, DatenoError::Unauthorized { status: 401, .. }));
+    assert!(err.to_string().contains("DATENO_API_KEY"));
+    mock.assert();
+}
+
+#[test]
+fn test_get_entry_returns_full_record() {
+    let mut server = Server::new();
+
+    let mock = server
+        .mock("GET", "/search/0.2/entry/dataset%2F1")
+        .with_status(200)
+        .with_body(r#"{"id": "dataset/1", "title": "Climate", "license": "CC-BY-4.0"}"#)
+        .create();
+
+    let client = DatenoClient::new(&server.url(), None).unwrap();
+    let dataset = client.get_entry("dataset/1").unwrap();
+
+    assert_eq!(dataset.id, "dataset/1");
+    assert_eq!(dataset.title, "Climate");
+    assert_eq!(dataset.raw.unwrap()["license"], "CC-BY-4.0");
+    mock.assert();
+}
+
+#[test]
+fn test_get_entry_not_found() {
+    let mut server = Server::new();
+
+    let mock = server
+        .mock("GET", "/search/0.2/entry/missing")
+        .with_status(404)
+        .with_body(r#"{"detail": "Not found"}"#)
+        .create();
+
+    let client = DatenoClient::new(&server.url(), None).unwrap();
+    let err = client.get_entry("missing").unwrap_err();
+
+    assert!(matches!(err, DatenoError::NotFound { status: 404, .. }));
+    mock.assert();
+}
+
+#[test]
+fn test_facets_flattens_buckets_in_request_order() {
+    let mut server = Server::new();
+
+    let mock = server
+        .mock("GET", "/search/0.2/query")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("q".into(), "climate".into()),
+            Matcher::UrlEncoded("limit".into(), "0".into()),
+            // Repeated keys are matched on the raw query string
+            Matcher::Regex("facets=format&facets=source.countries.name".into()),
+        ]))
+        .with_status(200)
+        .with_body(
+            r#"{"total": 30, "results": [], "facets": {
+                "source.countries.name": [{"key": "Canada", "doc_count": 12}],
+                "format": [{"value": "CSV", "count": 20}, {"value": "JSON", "count": 10}]
+            }}"#,
+        )
+        .create();
+
+    let client = DatenoClient::new(&server.url(), None).unwrap();
+    let counts = client
+        .facets(
+            "climate",
+            &[],
+            &["format".to_string(), "source.countries.name".to_string()],
+        )
+        .unwrap();
+
+    let rows: Vec<(&str, &str, u64)> = counts
+        .iter()
+        .map(|c| (c.facet.as_str(), c.value.as_str(), c.count))
+        .collect();
+    assert_eq!(
+        rows,
+        vec![
+            ("format", "CSV", 20),
+            ("format", "JSON", 10),
+            ("source.countries.name", "Canada", 12),
+        ]
+    );
+    mock.assert();
+}
+
+#[test]
+fn test_count_requests_zero_rows() {
+    let mut server = Server::new();
+
+    let mock = server
+        .mock("GET", "/search/0.2/query")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("q".into(), "climate".into()),
+            Matcher::UrlEncoded("limit".into(), "0".into()),
+        ]))
+        .with_status(200)
+        .with_body(r#"{"total": 4321, "results": []}"#)
+        .create();
+
+    let client = DatenoClient::new(&server.url(), None).unwrap();
+    assert_eq!(client.count("climate", &[]).unwrap(), 4321);
+    mock.assert();
+}
+
+#[test]
+fn test_search_parses_relevance_score() {
+    let mut server = Server::new();
+
+    let mock = server
+        .mock("GET", "/search/0.2/query")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_body(r#"{"total": 2, "results": [{"id": "a", "title": "A", "_score": 12.5}, {"id": "b", "title": "B"}]}"#)
+        .create();
+
+    let client = DatenoClient::new(&server.url(), None).unwrap();
+    let request = SearchRequest {
+        query: "climate".to_string(),
+        limit: 10,
+        ..Default::default()
     };
-    // This will fail because we don't have a mock server, but validation should pass
-    // The actual HTTP call will fail, but limit validation happens first
+
+    let response = client.search(request).unwrap();
+    assert_eq!(response.results[0].score, Some(12.5));
+    assert_eq!(response.results[1].score, None);
+    mock.assert();
+}
+
+#[test]
+fn test_search_responses_are_cached_per_api_key() {
+    let mut server = Server::new();
+
+    // A query no other test uses, since the cache is shared by the whole process
+    let mock = server
+        .mock("GET", "/search/0.2/query")
+        .match_query(Matcher::UrlEncoded("q".into(), "cached-ocean-salinity".into()))
+        .with_status(200)
+        .with_body(r#"{"total": 1, "results": [{"id": "a", "title": "A"}]}"#)
+        .expect(2)
+        .create();
+
+    let request = SearchRequest {
+        query: "cached-ocean-salinity".to_string(),
+        limit: 10,
+        ..Default::default()
+    };
+    let client = |api_key: &str| {
+        DatenoClient::new(&server.url(), Some(api_key.to_string()))
+            .unwrap()
+            .with_cache_policy(CachePolicy::default())
+    };
+
+    // The second call with the same key is answered from the cache
+    let first = client("key-1");
+    assert_eq!(first.search(request.clone()).unwrap().results[0].id, "a");
+    assert_eq!(first.search(request.clone()).unwrap().results[0].id, "a");
+
+    // A different key never sees another key's cached responses
+    assert!(client("key-2").search(request).is_ok());
+    mock.assert();
+}
+
+#[test]
+fn test_disk_cache_modes() {
+    let mut server = Server::new();
+    let dir = std::env::temp_dir().join(format!("dateno-disk-cache-{}", std::process::id()));
+
+    let mock = server
+        .mock("GET", "/search/0.2/query")
+        .match_query(Matcher::UrlEncoded("q".into(), "snapshot-glaciers".into()))
+        .with_status(200)
+        .with_body(r#"{"total": 1, "results": [{"id": "a", "title": "A"}]}"#)
+        .expect(2)
+        .create();
+
+    let request = SearchRequest {
+        query: "snapshot-glaciers".to_string(),
+        limit: 10,
+        ..Default::default()
+    };
+    // Memory caching off so every call exercises the disk cache
+    let client = |mode: DiskCacheMode| {
+        let policy = CachePolicy {
+            disk: Some(DiskCache { dir: dir.clone(), mode }),
+            ..CachePolicy::none()
+        };
+        DatenoClient::new(&server.url(), None).unwrap().with_cache_policy(policy)
+    };
+
+    // Nothing stored yet: read_only fails without touching the network
+    let err = client(DiskCacheMode::ReadOnly).search(request.clone()).unwrap_err();
+    assert!(matches!(err, DatenoError::CacheMiss { .. }));
+
+    // read_write fetches once and then replays from disk
+    assert!(client(DiskCacheMode::ReadWrite).search(request.clone()).is_ok());
+    assert!(client(DiskCacheMode::ReadWrite).search(request.clone()).is_ok());
+    assert_eq!(client(DiskCacheMode::ReadOnly).search(request.clone()).unwrap().results[0].id, "a");
+
+    // A projected query replays the same full-record snapshot
+    let projected = SearchRequest {
+        fields: vec!["id".to_string(), "title".to_string()],
+        ..request.clone()
+    };
+    assert_eq!(client(DiskCacheMode::ReadOnly).search(projected).unwrap().results[0].id, "a");
+
+    // refresh always goes to the API
+    assert!(client(DiskCacheMode::Refresh).search(request).is_ok());
+
+    mock.assert();
+    std::fs::remove_dir_all(dir).unwrap();
 }
@@ -9,8 +9,8 @@ fn test_parse_filter_valid() {
 
 #[test]
 fn test_parse_filter_with_equals_in_value() {
-    let (key, value) = parse_filter("key=value=with=equals").unwrap();
-    assert_eq!(key, "key");
+    let (key, value) = parse_filter("source.organizations.name=value=with=equals").unwrap();
+    assert_eq!(key, "source.organizations.name");
     assert_eq!(value, "value=with=equals");
 }
 
@@ -23,14 +23,21 @@ fn test_parse_filter_invalid_no_equals() {
 
 #[test]
 fn test_parse_filter_empty_key() {
-    let (key, value) = parse_filter("=value").unwrap();
-    assert_eq!(key, "");
-    assert_eq!(value, "value");
+    let result = parse_filter("=value");
+    assert!(result.is_err());
+    assert!(result.unwrap_err().to_string().contains("not a known filter field"));
+}
+
+#[test]
+fn test_parse_filter_unknown_key() {
+    let result = parse_filter("formt=CSV");
+    assert!(result.is_err());
+    assert!(result.unwrap_err().to_string().contains("'formt'"));
 }
 
 #[test]
 fn test_parse_filter_empty_value() {
-    let (key, value) = parse_filter("key=").unwrap();
-    assert_eq!(key, "key");
+    let (key, value) = parse_filter("format=").unwrap();
+    assert_eq!(key, "format");
     assert_eq!(value, "");
 }
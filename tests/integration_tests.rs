@@ -1,6 +1,6 @@
 #[cfg(feature = "integration-tests")]
 mod integration {
-    use dateno_duckdb_ext::api::{DatenoClient, SearchRequest};
+    use dateno_duckdb_ext::api::{DatenoClient, SearchRequest, DEFAULT_BASE_URL};
     use std::env;
 
     #[test]
@@ -9,11 +9,12 @@ mod integration {
         let api_key = env::var("DATENO_API_KEY")
             .expect("DATENO_API_KEY environment variable must be set");
         
-        let client = DatenoClient::new(Some(api_key)).unwrap();
+        let client = DatenoClient::new(DEFAULT_BASE_URL, Some(api_key)).unwrap();
         let request = SearchRequest {
             query: "climate".to_string(),
             filters: vec![],
             limit: 10,
+            ..Default::default()
         };
 
         let response = client.search(request);
@@ -30,11 +31,12 @@ mod integration {
         let api_key = env::var("DATENO_API_KEY")
             .expect("DATENO_API_KEY environment variable must be set");
         
-        let client = DatenoClient::new(Some(api_key)).unwrap();
+        let client = DatenoClient::new(DEFAULT_BASE_URL, Some(api_key)).unwrap();
         let request = SearchRequest {
             query: "temperature".to_string(),
             filters: vec!["format=CSV".to_string()],
             limit: 5,
+            ..Default::default()
         };
 
         let response = client.search(request);